@@ -0,0 +1,314 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::path::Path;
+use crate::plugins::combat::Team;
+use crate::plugins::combat_sim::simulate_combat;
+use crate::plugins::enemies::{EnemyDatabase, EnemyDefinition};
+use crate::plugins::inventory::{rotate_shape, InventoryGridState};
+use crate::plugins::items::{ItemDatabase, ItemDefinition, ItemType};
+use crate::plugins::metagame::{PersistentInventory, SavedItem};
+use crate::plugins::shop::roll_rarity;
+
+/// Items bought per shop round when generating an inventory.
+/// Gold is not modelled: every round buys this many items whatever they cost, so later days
+/// carry more (and pricier) gear than a player's purse would allow. Read the report as how
+/// items compare against each other, not as the odds of a realistic build.
+pub const PURCHASES_PER_ROUND: usize = 2;
+
+/// Settings for a balance run.
+#[derive(Debug, Clone)]
+pub struct BalanceConfig {
+    /// Days 1..=days are simulated
+    pub days: u32,
+    /// Random inventories generated per day; each fights every enemy available that day
+    pub runs_per_day: u32,
+    pub seed: u64,
+}
+
+impl Default for BalanceConfig {
+    fn default() -> Self {
+        Self { days: 10, runs_per_day: 200, seed: 0 }
+    }
+}
+
+/// Results against one enemy on one day.
+#[derive(Debug, Clone, Serialize)]
+pub struct MatchupStats {
+    pub day: u32,
+    pub enemy_id: String,
+    pub fights: u32,
+    pub wins: u32,
+    pub win_rate: f32,
+    pub avg_ticks: f32,
+}
+
+/// How often an item ended up in a generated inventory and how those inventories did.
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemBalance {
+    pub item_id: String,
+    /// Inventories the item was in
+    pub picks: u32,
+    pub fights: u32,
+    pub wins: u32,
+    pub win_rate: f32,
+    /// `win_rate` minus the overall win rate: positive means the item tends to win fights
+    pub contribution: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BalanceReport {
+    pub days: u32,
+    pub runs_per_day: u32,
+    pub seed: u64,
+    pub overall_win_rate: f32,
+    pub matchups: Vec<MatchupStats>,
+    pub items: Vec<ItemBalance>,
+}
+
+/// Builds an inventory roughly like a player's by `day`: starting from the default
+/// inventory, buy `PURCHASES_PER_ROUND` items per round using the shop's rarity tables
+/// (ignoring price) and put each where `InventoryGridState` allows it. Items that don't fit
+/// anywhere are left out.
+pub fn random_inventory(item_db: &ItemDatabase, day: u32, rng: &mut impl Rng) -> PersistentInventory {
+    let mut inventory = PersistentInventory::default();
+    let mut grid = InventoryGridState::default();
+    for (index, saved) in inventory.items.iter().enumerate() {
+        if let Some(def) = item_db.items.get(&saved.item_id) {
+            occupy(&mut grid, def, IVec2::new(saved.grid_x, saved.grid_y), saved.rotation, Entity::from_raw(index as u32));
+        }
+    }
+
+    for round in 1..=day.max(1) {
+        for purchase in 0..PURCHASES_PER_ROUND {
+            let rarity = roll_rarity(round, rng, purchase == 0);
+            // Sorted so a seeded rng always picks the same item
+            let mut candidates: Vec<&ItemDefinition> = item_db.items.values().filter(|i| i.rarity == rarity).collect();
+            candidates.sort_by(|a, b| a.id.cmp(&b.id));
+            let Some(def) = candidates.choose(rng) else { continue; };
+
+            let Some((pos, rot)) = random_spot(&grid, def, rng) else { continue; };
+            occupy(&mut grid, def, pos, rot, Entity::from_raw(inventory.items.len() as u32));
+            inventory.items.push(SavedItem::new(def.id.clone(), pos.x, pos.y, rot));
+        }
+    }
+
+    inventory
+}
+
+/// A random legal position and rotation for `def`. Bags must also touch an existing slot
+/// so the grid stays in one piece.
+fn random_spot(grid: &InventoryGridState, def: &ItemDefinition, rng: &mut impl Rng) -> Option<(IVec2, u8)> {
+    let is_bag = matches!(def.item_type, ItemType::Bag { .. });
+    // Room for a bag to hang off any edge
+    let margin = if is_bag { def.width.max(def.height) as i32 } else { 0 };
+    let min = grid.bounds.min - IVec2::splat(margin);
+    let max = grid.bounds.max + IVec2::splat(margin);
+
+    let mut spots = Vec::new();
+    for y in min.y..=max.y {
+        for x in min.x..=max.x {
+            let pos = IVec2::new(x, y);
+            for rot in 0..4 {
+                let legal = if is_bag {
+                    grid.can_place_bag(&def.shape, pos, rot, None) && touches_slots(grid, &def.shape, pos, rot)
+                } else {
+                    grid.can_place_item(&def.shape, pos, rot, None)
+                };
+                if legal {
+                    spots.push((pos, rot));
+                }
+            }
+        }
+    }
+    spots.choose(rng).copied()
+}
+
+fn touches_slots(grid: &InventoryGridState, shape: &[IVec2], pos: IVec2, rot: u8) -> bool {
+    rotate_shape(shape, rot).iter().any(|offset| {
+        [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y]
+            .iter()
+            .any(|dir| grid.slots.contains_key(&(pos + *offset + *dir)))
+    })
+}
+
+/// Marks `def`'s cells as taken, the same way `InventoryGridState::rebuild` does for spawned items.
+fn occupy(grid: &mut InventoryGridState, def: &ItemDefinition, pos: IVec2, rot: u8, entity: Entity) {
+    for offset in rotate_shape(&def.shape, rot) {
+        let cell = pos + offset;
+        if matches!(def.item_type, ItemType::Bag { .. }) {
+            grid.slots.insert(cell, entity);
+            grid.bounds.min = grid.bounds.min.min(cell);
+            grid.bounds.max = grid.bounds.max.max(cell);
+        } else {
+            grid.occupancy.insert(cell, entity);
+        }
+    }
+}
+
+/// Generates `runs_per_day` inventories per day and fights each against every enemy
+/// available that day, one at a time. Same config and databases give the same report.
+pub fn run_balance(item_db: &ItemDatabase, enemy_db: &EnemyDatabase, config: &BalanceConfig) -> BalanceReport {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut enemies: Vec<&EnemyDefinition> = enemy_db.enemies.values().collect();
+    enemies.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let mut matchups = Vec::new();
+    // Item id -> (picks, fights, wins)
+    let mut per_item: HashMap<String, (u32, u32, u32)> = HashMap::new();
+    let (mut total_fights, mut total_wins) = (0, 0);
+
+    for day in 1..=config.days {
+        let lineup: Vec<EnemyDefinition> = enemies.iter()
+            .filter(|e| e.min_day <= day)
+            .map(|e| e.scaled_for_day(day))
+            .collect();
+        // Enemy index -> (wins, ticks)
+        let mut results = vec![(0u32, 0u64); lineup.len()];
+
+        for _ in 0..config.runs_per_day {
+            let inventory = random_inventory(item_db, day, &mut rng);
+            let mut wins = 0;
+            for (index, enemy) in lineup.iter().enumerate() {
+                let result = simulate_combat(&inventory, item_db, std::slice::from_ref(enemy), rng.gen());
+                if result.winner == Some(Team::Player) {
+                    results[index].0 += 1;
+                    wins += 1;
+                }
+                results[index].1 += result.ticks as u64;
+            }
+
+            let mut ids: Vec<&String> = inventory.items.iter().map(|i| &i.item_id).collect();
+            ids.sort();
+            ids.dedup();
+            for id in ids {
+                let entry = per_item.entry(id.clone()).or_default();
+                entry.0 += 1;
+                entry.1 += lineup.len() as u32;
+                entry.2 += wins;
+            }
+            total_fights += lineup.len() as u32;
+            total_wins += wins;
+        }
+
+        for (enemy, (wins, ticks)) in lineup.iter().zip(results) {
+            let fights = config.runs_per_day;
+            matchups.push(MatchupStats {
+                day,
+                enemy_id: enemy.id.clone(),
+                fights,
+                wins,
+                win_rate: ratio(wins, fights),
+                avg_ticks: if fights == 0 { 0.0 } else { ticks as f32 / fights as f32 },
+            });
+        }
+    }
+
+    let overall_win_rate = ratio(total_wins, total_fights);
+    let mut items: Vec<ItemBalance> = per_item.into_iter()
+        .map(|(item_id, (picks, fights, wins))| ItemBalance {
+            item_id,
+            picks,
+            fights,
+            wins,
+            win_rate: ratio(wins, fights),
+            contribution: ratio(wins, fights) - overall_win_rate,
+        })
+        .collect();
+    items.sort_by(|a, b| a.item_id.cmp(&b.item_id));
+
+    BalanceReport {
+        days: config.days,
+        runs_per_day: config.runs_per_day,
+        seed: config.seed,
+        overall_win_rate,
+        matchups,
+        items,
+    }
+}
+
+fn ratio(part: u32, whole: u32) -> f32 {
+    if whole == 0 { 0.0 } else { part as f32 / whole as f32 }
+}
+
+impl BalanceReport {
+    pub fn matchups_csv(&self) -> String {
+        let mut csv = String::from("day,enemy_id,fights,wins,win_rate,avg_ticks\n");
+        for m in &self.matchups {
+            let _ = writeln!(csv, "{},{},{},{},{:.4},{:.1}", m.day, m.enemy_id, m.fights, m.wins, m.win_rate, m.avg_ticks);
+        }
+        csv
+    }
+
+    pub fn items_csv(&self) -> String {
+        let mut csv = String::from("item_id,picks,fights,wins,win_rate,contribution\n");
+        for i in &self.items {
+            let _ = writeln!(csv, "{},{},{},{},{:.4},{:.4}", i.item_id, i.picks, i.fights, i.wins, i.win_rate, i.contribution);
+        }
+        csv
+    }
+
+    /// Writes `matchups.csv`, `items.csv` and `report.json` into `dir`, creating it if needed.
+    pub fn write_to_dir(&self, dir: impl AsRef<Path>) -> std::io::Result<()> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(dir.join("matchups.csv"), self.matchups_csv())?;
+        std::fs::write(dir.join("items.csv"), self.items_csv())?;
+        let json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(dir.join("report.json"), json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::utils::HashSet;
+
+    fn load() -> (ItemDatabase, EnemyDatabase) {
+        (ItemDatabase::shipped(), EnemyDatabase::shipped())
+    }
+
+    #[test]
+    fn test_random_inventory_is_legal() {
+        let (item_db, _) = load();
+        let mut rng = StdRng::seed_from_u64(11);
+
+        for day in 1..=10 {
+            let inventory = random_inventory(&item_db, day, &mut rng);
+            let (mut slots, mut taken) = (HashSet::new(), HashSet::new());
+            let is_bag = |s: &SavedItem| matches!(item_db.items[&s.item_id].item_type, ItemType::Bag { .. });
+
+            for saved in inventory.items.iter().filter(|s| is_bag(s)) {
+                for offset in rotate_shape(&item_db.items[&saved.item_id].shape, saved.rotation) {
+                    assert!(slots.insert(IVec2::new(saved.grid_x, saved.grid_y) + offset), "bags overlap");
+                }
+            }
+            for saved in inventory.items.iter().filter(|s| !is_bag(s)) {
+                for offset in rotate_shape(&item_db.items[&saved.item_id].shape, saved.rotation) {
+                    let cell = IVec2::new(saved.grid_x, saved.grid_y) + offset;
+                    assert!(slots.contains(&cell), "{} is off the bags", saved.item_id);
+                    assert!(taken.insert(cell), "{} overlaps another item", saved.item_id);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_balance_report_is_reproducible() {
+        let (item_db, enemy_db) = load();
+        let config = BalanceConfig { days: 3, runs_per_day: 4, seed: 5 };
+
+        let first = run_balance(&item_db, &enemy_db, &config);
+        let second = run_balance(&item_db, &enemy_db, &config);
+
+        assert_eq!(first.matchups_csv(), second.matchups_csv());
+        assert_eq!(first.items_csv(), second.items_csv());
+        // Day 3 adds the wraith
+        assert_eq!(first.matchups.iter().filter(|m| m.day == 3).count(), 3);
+        assert!(first.items.iter().any(|i| i.item_id == "starter_bag" && i.picks == 12));
+    }
+}
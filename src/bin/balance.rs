@@ -0,0 +1,63 @@
+//! Headless balance simulation. Fights random inventories against every enemy for each day
+//! and writes win rates and item stats as CSV and JSON. Opens no window, so it runs on CI.
+//!
+//! Usage: balance [--days N] [--runs N] [--seed N] [--out DIR]
+
+use cursed_warden::balance::{run_balance, BalanceConfig};
+use cursed_warden::plugins::enemies::{EnemyDatabase, ENEMIES_ASSET_DIR};
+use cursed_warden::plugins::items::{ItemDatabase, ITEMS_ASSET_DIR};
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let mut config = BalanceConfig::default();
+    let mut out_dir = String::from("balance_report");
+
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let Some(value) = args.next() else {
+            eprintln!("missing value for {}", flag);
+            return ExitCode::FAILURE;
+        };
+        let parsed = match flag.as_str() {
+            "--days" => value.parse().map(|v| config.days = v).is_ok(),
+            "--runs" => value.parse().map(|v| config.runs_per_day = v).is_ok(),
+            "--seed" => value.parse().map(|v| config.seed = v).is_ok(),
+            "--out" => { out_dir = value.clone(); true }
+            _ => {
+                eprintln!("unknown argument {}", flag);
+                return ExitCode::FAILURE;
+            }
+        };
+        if !parsed {
+            eprintln!("invalid value '{}' for {}", value, flag);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let item_db = match ItemDatabase::load_from_dir(ITEMS_ASSET_DIR) {
+        Ok(db) => db,
+        Err(e) => {
+            eprintln!("Failed to load item database: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let enemy_db = match EnemyDatabase::load_from_dir(ENEMIES_ASSET_DIR) {
+        Ok(db) => db,
+        Err(e) => {
+            eprintln!("Failed to load enemy database: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let report = run_balance(&item_db, &enemy_db, &config);
+    if let Err(e) = report.write_to_dir(&out_dir) {
+        eprintln!("Failed to write report to {}: {}", out_dir, e);
+        return ExitCode::FAILURE;
+    }
+
+    println!(
+        "{} days x {} runs: overall win rate {:.1}%, report written to {}",
+        report.days, report.runs_per_day, report.overall_win_rate * 100.0, out_dir
+    );
+    ExitCode::SUCCESS
+}
@@ -1,3 +1,4 @@
+pub mod balance;
 pub mod plugins;
 pub use plugins::combat;
 pub use plugins::core;
@@ -1,10 +1,15 @@
 use bevy::prelude::*;
 
 use cursed_warden::plugins::combat::CombatPlugin;
+use cursed_warden::plugins::combat_fx::CombatFxPlugin;
+use cursed_warden::plugins::combat_log::CombatLogPlugin;
 use cursed_warden::plugins::core::CorePlugin;
+use cursed_warden::plugins::crafting::CraftingPlugin;
+use cursed_warden::plugins::enemies::EnemiesPlugin;
 use cursed_warden::plugins::inventory::InventoryPlugin;
 use cursed_warden::plugins::items::ItemsPlugin;
 use cursed_warden::plugins::metagame::MetagamePlugin;
+use cursed_warden::plugins::outcome::OutcomePlugin;
 use cursed_warden::plugins::ui::UiPlugin;
 use cursed_warden::plugins::shop::ShopPlugin;
 use cursed_warden::plugins::visualization::VisualizationPlugin;
@@ -15,7 +20,12 @@ fn main() {
         .add_plugins(CorePlugin)
         .add_plugins(InventoryPlugin)
         .add_plugins(ItemsPlugin)
+        .add_plugins(CraftingPlugin)
         .add_plugins(CombatPlugin)
+        .add_plugins(CombatLogPlugin)
+        .add_plugins(CombatFxPlugin)
+        .add_plugins(OutcomePlugin)
+        .add_plugins(EnemiesPlugin)
         .add_plugins(MetagamePlugin)
         .add_plugins(UiPlugin)
         .add_plugins(ShopPlugin)
@@ -0,0 +1,233 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use crate::plugins::combat::{ActionMeter, CombatEvent, CombatWinner, Health, MaterialType, WeaponOf};
+use crate::plugins::inventory_utils::{AmmoStats, WeaponStats};
+
+/// Rounds carried into the fight by the unit in `AmmoOf`. Shared by every ranged weapon in reach,
+/// and full again next fight.
+#[derive(Component, Debug, Clone)]
+pub struct Ammo {
+    pub item_id: String,
+    /// Index into `PersistentInventory::items`
+    pub inventory_index: usize,
+    pub material: MaterialType,
+    pub rounds_left: u32,
+}
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct AmmoOf(pub Entity);
+
+/// Where a ranged weapon gets its rounds. Present only on ranged weapons.
+#[derive(Component, Debug, Clone)]
+pub struct AmmoSupply {
+    /// Ammo entities in draw order
+    pub sources: Vec<Entity>,
+    /// Material the weapon hits with when nothing is loaded
+    pub own_material: MaterialType,
+    /// Fraction of attack dealt without ammo; 0 means the weapon holds its fire
+    pub empty_damage: f32,
+    /// Ammo the swing about to happen draws from; the round is only taken once the shot lands
+    pub loaded: Option<Entity>,
+    /// Out of ammo has already been reported
+    pub dry: bool,
+}
+
+impl AmmoSupply {
+    /// Multiplier on the weapon's attack for the current swing.
+    pub fn damage_factor(&self) -> f32 {
+        if self.loaded.is_some() { 1.0 } else { self.empty_damage }
+    }
+}
+
+/// Combat components for one of `holder`'s ammo items.
+pub fn ammo_unit(holder: Entity, ammo: &AmmoStats, label: String) -> impl Bundle {
+    (
+        Name::new(label),
+        AmmoOf(holder),
+        Ammo {
+            item_id: ammo.item_id.clone(),
+            inventory_index: ammo.inventory_index,
+            material: ammo.material,
+            rounds_left: ammo.rounds,
+        },
+    )
+}
+
+/// Supply for a ranged weapon, given the spawned ammo entities by inventory index.
+/// `None` for melee weapons.
+pub fn ammo_supply(weapon: &WeaponStats, ammo_entities: &HashMap<usize, Entity>) -> Option<AmmoSupply> {
+    let ranged = weapon.ranged?;
+    Some(AmmoSupply {
+        sources: weapon.ammo.iter().filter_map(|index| ammo_entities.get(index).copied()).collect(),
+        own_material: weapon.material,
+        empty_damage: ranged.empty_damage,
+        loaded: None,
+        dry: false,
+    })
+}
+
+/// Loads ranged weapons whose meter is full and whose wielder is alive: picks the first ammo
+/// with a round not already loaded into another weapon this tick and switches the weapon to the
+/// ammo's material. With nothing left the weapon falls back to its own material and
+/// `empty_damage`, or skips the swing entirely. The round itself is taken by `spend_ammo_system`.
+/// Runs right before `combat_turn_system`.
+pub fn load_ammo_system(
+    mut q_weapons: Query<(Entity, &mut AmmoSupply, &mut MaterialType, &mut ActionMeter, &WeaponOf)>,
+    q_ammo: Query<&Ammo>,
+    q_health: Query<&Health>,
+    winner: Res<CombatWinner>,
+    mut ev_combat: EventWriter<CombatEvent>,
+) {
+    if winner.0.is_some() { return; }
+
+    let mut ready: Vec<Entity> = q_weapons.iter()
+        .filter(|(_, _, _, meter, wielder)| {
+            meter.value >= meter.threshold && q_health.get(wielder.0).is_ok_and(|h| h.current > 0.0)
+        })
+        .map(|(entity, _, _, _, _)| entity)
+        .collect();
+    // Weapons sharing a quiver draw in a stable order
+    ready.sort();
+
+    // Rounds loaded this tick, per ammo entity
+    let mut reserved: HashMap<Entity, u32> = HashMap::new();
+    for entity in ready {
+        let Ok((_, mut supply, mut material, mut meter, _)) = q_weapons.get_mut(entity) else { continue; };
+
+        let round = supply.sources.iter().copied().find_map(|source| {
+            let ammo = q_ammo.get(source).ok()?;
+            (ammo.rounds_left > reserved.get(&source).copied().unwrap_or(0)).then_some((source, ammo.material))
+        });
+        if let Some((source, ammo_material)) = round {
+            *reserved.entry(source).or_default() += 1;
+            *material = ammo_material;
+            supply.loaded = Some(source);
+            continue;
+        }
+
+        *material = supply.own_material;
+        supply.loaded = None;
+        if !supply.dry {
+            supply.dry = true;
+            ev_combat.send(CombatEvent::OutOfAmmo { weapon: entity });
+        }
+        if supply.empty_damage <= 0.0 {
+            meter.value -= meter.threshold;
+        }
+    }
+}
+
+/// Takes one round for every loaded weapon that actually attacked this tick.
+/// Runs right after `combat_turn_system`.
+pub fn spend_ammo_system(
+    mut ev_combat: EventReader<CombatEvent>,
+    mut q_supplies: Query<&mut AmmoSupply>,
+    mut q_ammo: Query<&mut Ammo>,
+) {
+    for event in ev_combat.read() {
+        let CombatEvent::Attack { attacker, .. } = event else { continue; };
+        let Ok(mut supply) = q_supplies.get_mut(*attacker) else { continue; };
+        let Some(source) = supply.loaded.take() else { continue; };
+        if let Ok(mut ammo) = q_ammo.get_mut(source) {
+            ammo.rounds_left = ammo.rounds_left.saturating_sub(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::combat::{damage_breakdown, UnitType};
+    use crate::plugins::items::RangedDefinition;
+
+    fn bow() -> WeaponStats {
+        WeaponStats {
+            item_id: "bow".to_string(),
+            inventory_index: 0,
+            damage: 10.0,
+            speed: 0.0,
+            material: MaterialType::Flesh,
+            targeting: default(),
+            on_hit: Vec::new(),
+            ranged: Some(RangedDefinition { empty_damage: 0.0 }),
+            ammo: vec![1],
+        }
+    }
+
+    fn shot(world: &mut World, weapon: Entity) {
+        let target = world.spawn_empty().id();
+        world.send_event(CombatEvent::Attack {
+            attacker: weapon,
+            target,
+            hit: damage_breakdown(10.0, MaterialType::Silver, UnitType::Monster, 0.0),
+            remaining_health: 0.0,
+        });
+    }
+
+    #[test]
+    fn test_ranged_weapon_draws_ammo_then_runs_dry() {
+        let mut world = World::new();
+        world.init_resource::<CombatWinner>();
+        world.init_resource::<Events<CombatEvent>>();
+
+        let holder = world.spawn(Health { current: 10.0, max: 10.0 }).id();
+        let arrows = AmmoStats { item_id: "arrows".to_string(), inventory_index: 1, material: MaterialType::Silver, rounds: 1 };
+        let ammo = world.spawn(ammo_unit(holder, &arrows, "Arrows".to_string())).id();
+
+        let bow = bow();
+        let supply = ammo_supply(&bow, &[(1, ammo)].into_iter().collect()).unwrap();
+        let full = ActionMeter { value: 1000.0, threshold: 1000.0 };
+        let weapon = world.spawn((supply, bow.material, full, WeaponOf(holder))).id();
+
+        let mut load = Schedule::default();
+        load.add_systems(load_ammo_system);
+        let mut spend = Schedule::default();
+        spend.add_systems(spend_ammo_system);
+
+        // Loaded with the silver round, which stays in the quiver until the shot goes off
+        load.run(&mut world);
+        assert_eq!(*world.get::<MaterialType>(weapon).unwrap(), MaterialType::Silver);
+        assert_eq!(world.get::<AmmoSupply>(weapon).unwrap().damage_factor(), 1.0);
+        spend.run(&mut world);
+        assert_eq!(world.get::<Ammo>(ammo).unwrap().rounds_left, 1);
+
+        shot(&mut world, weapon);
+        spend.run(&mut world);
+        assert_eq!(world.get::<Ammo>(ammo).unwrap().rounds_left, 0);
+
+        // Empty with no fallback: back to its own material and the swing is skipped
+        load.run(&mut world);
+        assert_eq!(*world.get::<MaterialType>(weapon).unwrap(), MaterialType::Flesh);
+        assert_eq!(world.get::<ActionMeter>(weapon).unwrap().value, 0.0);
+        assert_eq!(world.get::<AmmoSupply>(weapon).unwrap().damage_factor(), 0.0);
+    }
+
+    #[test]
+    fn test_no_ammo_for_dead_or_shared_shots() {
+        let mut world = World::new();
+        world.init_resource::<CombatWinner>();
+        world.init_resource::<Events<CombatEvent>>();
+
+        let holder = world.spawn(Health { current: 10.0, max: 10.0 }).id();
+        let arrows = AmmoStats { item_id: "arrows".to_string(), inventory_index: 1, material: MaterialType::Silver, rounds: 1 };
+        let ammo = world.spawn(ammo_unit(holder, &arrows, "Arrows".to_string())).id();
+        let ammo_entities = [(1, ammo)].into_iter().collect();
+        let full = ActionMeter { value: 1000.0, threshold: 1000.0 };
+        let first = world.spawn((ammo_supply(&bow(), &ammo_entities).unwrap(), MaterialType::Flesh, full, WeaponOf(holder))).id();
+        let second = world.spawn((ammo_supply(&bow(), &ammo_entities).unwrap(), MaterialType::Flesh, full, WeaponOf(holder))).id();
+
+        // Two bows on one quiver with a single round: only the first gets it
+        let mut schedule = Schedule::default();
+        schedule.add_systems(load_ammo_system);
+        schedule.run(&mut world);
+        assert_eq!(world.get::<AmmoSupply>(first).unwrap().loaded, Some(ammo));
+        assert_eq!(world.get::<AmmoSupply>(second).unwrap().loaded, None);
+
+        // A dead wielder's bows aren't loaded at all
+        world.get_mut::<Health>(holder).unwrap().current = 0.0;
+        world.get_mut::<AmmoSupply>(first).unwrap().loaded = None;
+        schedule.run(&mut world);
+        assert_eq!(world.get::<AmmoSupply>(first).unwrap().loaded, None);
+        assert_eq!(world.get::<Ammo>(ammo).unwrap().rounds_left, 1);
+    }
+}
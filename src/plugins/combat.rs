@@ -1,4 +1,21 @@
+use bevy::ecs::schedule::SystemConfigs;
 use bevy::prelude::*;
+use bevy::utils::HashMap;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use crate::plugins::combat_log::{record_combat_events, register_combat_units, CombatLog, CombatReplay};
+use crate::plugins::enemies::{pick_encounter, Enemy, EnemyDatabase, EnemyDefinition};
+use crate::plugins::ammo::{ammo_supply, ammo_unit, load_ammo_system, spend_ammo_system, Ammo, AmmoOf, AmmoSupply};
+use crate::plugins::combat_fx::FloatingText;
+use crate::plugins::consumables::{consumable_system, consumable_unit, Consumable, ConsumableOf};
+use crate::plugins::inventory_utils::{CombatStats, WeaponStats};
+use crate::plugins::outcome::NightEncounter;
+use crate::plugins::items::StatType;
+use crate::plugins::status_effects::{status_tick_system, OnHit, StatusEffects, StatusKind};
+
+/// Player health before any item bonuses.
+pub const PLAYER_BASE_HEALTH: f32 = 100.0;
 
 pub struct CombatPlugin;
 
@@ -12,48 +29,136 @@ impl Plugin for CombatPlugin {
             .register_type::<MaterialType>()
             .register_type::<UnitType>()
             .register_type::<Team>()
-            .add_systems(OnEnter(crate::plugins::core::GameState::NightPhase), spawn_combat_arena)
+            .register_type::<TargetingStrategy>()
+            .register_type::<CombatSlot>()
+            .register_type::<StatusEffects>()
+            .add_event::<CombatEvent>()
+            .init_resource::<CombatWinner>()
+            .init_resource::<CombatLog>()
+            .insert_resource(CombatRng::from_seed(0))
+            .add_systems(OnEnter(crate::plugins::core::GameState::NightPhase), spawn_combat_arena.run_if(not(resource_exists::<CombatReplay>)))
             .add_systems(OnExit(crate::plugins::core::GameState::NightPhase), cleanup_combat_ui)
-            .add_systems(FixedUpdate, (tick_timer_system, combat_turn_system).chain().run_if(in_state(crate::plugins::core::GameState::NightPhase)))
-            .add_systems(Update, update_combat_ui.run_if(in_state(crate::plugins::core::GameState::NightPhase)));
+            .add_systems(FixedUpdate, combat_rules().run_if(in_state(crate::plugins::core::GameState::NightPhase)).run_if(not(resource_exists::<CombatReplay>)))
+            .add_systems(Update, (update_combat_ui, update_consumable_ui, update_ammo_ui).run_if(in_state(crate::plugins::core::GameState::NightPhase)));
     }
 }
 
-// Marker Components for Combat UI
-#[derive(Component)]
-pub struct CombatLog;
+/// The combat rules in the order they run each tick. Shared by the arena and the headless simulator.
+pub fn combat_rules() -> SystemConfigs {
+    (register_combat_units, tick_timer_system, status_tick_system, consumable_system, load_ammo_system, combat_turn_system, spend_ammo_system, record_combat_events).chain()
+}
+
+/// Something that happened during a fight, in the order it happened.
+#[derive(Event, Debug, Clone, PartialEq)]
+pub enum CombatEvent {
+    Attack { attacker: Entity, target: Entity, hit: DamageBreakdown, remaining_health: f32 },
+    StatusApplied { target: Entity, kind: StatusKind, stacks: u32 },
+    StatusDamage { unit: Entity, kind: StatusKind, damage: f32, remaining_health: f32 },
+    ConsumableUsed { unit: Entity, item: String },
+    /// A ranged weapon found no ammo left to draw
+    OutOfAmmo { weapon: Entity },
+    Healed { unit: Entity, amount: f32, remaining_health: f32 },
+    Death { unit: Entity },
+    Victory { winner: Team },
+}
+
+/// Winning team of the current fight, `None` while it's still going.
+#[derive(Resource, Default, Debug, Clone, Copy)]
+pub struct CombatWinner(pub Option<Team>);
+
+/// Randomness used by combat rules. Seeded per fight so simulations can be replayed.
+#[derive(Resource)]
+pub struct CombatRng {
+    /// What the fight was seeded with; also recorded in `CombatLog::seed`
+    pub seed: u64,
+    pub rng: StdRng,
+}
+
+impl CombatRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self { seed, rng: StdRng::seed_from_u64(seed) }
+    }
+}
 
+// Marker Components for Combat UI
 #[derive(Component)]
 pub struct CombatUnitUi;
 
+/// An attacker acting on behalf of another unit (e.g. one of the player's weapons).
+/// It has its own `ActionMeter`, `Attack`, `Speed` and `MaterialType`, and stops when the wielder dies.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct WeaponOf(pub Entity);
+
+/// Display name for a weapon row in the arena.
+#[derive(Component, Debug, Clone)]
+pub struct WeaponLabel(pub String);
+
 fn cleanup_combat_ui(mut commands: Commands, q_root: Query<Entity, With<CombatUnitUi>>) {
     for e in q_root.iter() {
         commands.entity(e).despawn_recursive();
     }
 }
 
-#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[reflect(Component)]
 pub enum Team {
     Player,
     Enemy,
 }
 
+/// How an attacker picks its target among living enemies.
+#[derive(Component, Reflect, Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[reflect(Component)]
+pub enum TargetingStrategy {
+    /// Lowest `CombatSlot`
+    #[default]
+    FrontMost,
+    LowestHealth,
+    /// Most damage per tick across everything the target attacks with
+    HighestThreat,
+    /// Uniform pick drawn from `CombatRng`
+    Random,
+    /// Best material efficiency against the target's `UnitType`
+    MaterialEffective,
+}
+
+/// Position in a team's formation, 0 being the front. Also the stable order targets are considered in.
+#[derive(Component, Reflect, Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[reflect(Component)]
+pub struct CombatSlot(pub u32);
+
 // Systems
 fn spawn_combat_arena(
     mut commands: Commands,
     q_existing: Query<Entity, With<CombatUnitUi>>,
     persistent_inventory: Res<crate::plugins::metagame::PersistentInventory>,
     item_db: Res<crate::plugins::items::ItemDatabase>,
+    enemy_db: Res<EnemyDatabase>,
+    global_time: Res<crate::plugins::metagame::GlobalTime>,
+    mut winner: ResMut<CombatWinner>,
 ) {
+    winner.0 = None;
+    // One seed decides the encounter and every roll in the fight, so the log is enough to reproduce it
+    let seed: u64 = rand::random();
+    info!("Night fight seed: {}", seed);
+    commands.insert_resource(CombatRng::from_seed(seed));
+    commands.insert_resource(CombatLog::with_seed(seed));
+
     // Clean up if re-entering (though ideally we track persistence)
     for e in q_existing.iter() {
         commands.entity(e).despawn_recursive();
     }
 
     let stats = crate::plugins::inventory::calculate_combat_stats(&persistent_inventory, &item_db);
-    let base_hp = 100.0;
-    let final_hp = base_hp + stats.health;
+
+    let mut encounter = pick_encounter(&enemy_db, global_time.day, &mut StdRng::seed_from_u64(seed));
+    if encounter.is_empty() {
+        warn!("No enemies available for day {}, using a default monster", global_time.day);
+        encounter.push(default_monster());
+    }
+    commands.insert_resource(NightEncounter(encounter.clone()));
+    let final_hp = player_max_health(&stats);
+    let weapons = player_weapons(&stats);
 
     // Spawn Arena UI Container
     commands.spawn((
@@ -91,17 +196,62 @@ fn spawn_combat_arena(
                 TextFont { font_size: 16.0, ..default() },
                 TextColor(Color::WHITE),
              ));
+
+             // Ammo first, so ranged weapons know where to draw from
+             let player = p.parent_entity();
+             let mut ammo_entities = HashMap::new();
+             for ammo in &stats.ammo {
+                 let entity = p.spawn((
+                    Node { margin: UiRect::top(Val::Px(4.0)), ..default() },
+                    ammo_unit(player, ammo, item_label(&item_db, &ammo.item_id)),
+                 )).with_children(|row| {
+                     row.spawn((
+                        Text::new(""),
+                        TextFont { font_size: 12.0, ..default() },
+                        TextColor(Color::srgb(1.0, 0.9, 0.6)),
+                     ));
+                 }).id();
+                 ammo_entities.insert(ammo.inventory_index, entity);
+             }
+
+             // Each weapon attacks on its own timer
+             for weapon in &weapons {
+                 let label = item_label(&item_db, &weapon.item_id);
+                 let mut row = p.spawn((
+                    Node {
+                        margin: UiRect::top(Val::Px(4.0)),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    weapon_unit(player, weapon, label.clone()),
+                 ));
+                 if let Some(supply) = ammo_supply(weapon, &ammo_entities) {
+                     row.insert(supply);
+                 }
+                 row.with_children(|row| {
+                     row.spawn((
+                        Text::new(label),
+                        TextFont { font_size: 12.0, ..default() },
+                        TextColor(Color::srgb(0.8, 0.8, 1.0)),
+                     ));
+                 });
+             }
+
+             for consumable in &stats.consumables {
+                 p.spawn((
+                    Node { margin: UiRect::top(Val::Px(4.0)), ..default() },
+                    consumable_unit(player, consumable, item_label(&item_db, &consumable.item_id)),
+                 )).with_children(|row| {
+                     row.spawn((
+                        Text::new(""),
+                        TextFont { font_size: 12.0, ..default() },
+                        TextColor(Color::srgb(0.6, 1.0, 0.6)),
+                     ));
+                 });
+             }
         })
-        .insert((
-            Health { current: final_hp, max: final_hp },
-            Attack { value: stats.attack.max(1.0) },
-            Defense { value: stats.defense },
-            Speed { value: stats.speed.max(5.0) },
-            ActionMeter::default(),
-            UnitType::Human,
-            MaterialType::Steel,
-            Team::Player,
-        ));
+        .insert(player_unit(&stats));
 
         // VS Text
         parent.spawn((
@@ -111,45 +261,152 @@ fn spawn_combat_arena(
         ));
 
         // Enemy Side
-        parent.spawn((
-            Node {
-                width: Val::Px(200.0),
-                height: Val::Px(300.0),
-                border: UiRect::all(Val::Px(2.0)),
-                display: Display::Flex,
-                flex_direction: FlexDirection::Column,
-                align_items: AlignItems::Center,
-                justify_content: JustifyContent::Center,
-                ..default()
-            },
-            BorderColor(Color::srgb(1.0, 0.0, 0.0)),
-            BackgroundColor(Color::srgb(0.5, 0.2, 0.2)),
-        ))
-        .with_children(|p| {
-             p.spawn((
-                Text::new("Enemy Monster\nMonster\nHP: 150/150"),
-                TextFont { font_size: 16.0, ..default() },
-                TextColor(Color::WHITE),
-             ));
+        parent.spawn(Node {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(10.0),
+            ..default()
         })
-        .insert((
-            Health { current: 150.0, max: 150.0 },
-            Attack { value: 15.0 },
-            Defense { value: 2.0 },
-            Speed { value: 10.0 },
-            ActionMeter::default(),
-            UnitType::Monster,
-            MaterialType::Flesh,
-            Team::Enemy,
-        ));
+        .with_children(|column| {
+            for (slot, enemy) in encounter.iter().enumerate() {
+                column.spawn((
+                    Node {
+                        width: Val::Px(200.0),
+                        height: Val::Px(300.0 / encounter.len() as f32 - 10.0),
+                        border: UiRect::all(Val::Px(2.0)),
+                        display: Display::Flex,
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                    BorderColor(Color::srgb(1.0, 0.0, 0.0)),
+                    BackgroundColor(Color::srgb(0.5, 0.2, 0.2)),
+                ))
+                .with_children(|p| {
+                     p.spawn((
+                        Text::new(format!("{}\nHP: {:.0}/{:.0}", enemy.name, enemy.health, enemy.health)),
+                        TextFont { font_size: 16.0, ..default() },
+                        TextColor(Color::WHITE),
+                     ));
+                })
+                .insert(enemy_unit(enemy, slot as u32));
+            }
+        });
     });
 }
 
+/// Base health plus item health. Items can cost health, but never below 1.
+pub fn player_max_health(stats: &CombatStats) -> f32 {
+    (PLAYER_BASE_HEALTH + stats.health).max(1.0)
+}
+
+/// Combat components for the player, with stats from the inventory.
+pub fn player_unit(stats: &CombatStats) -> impl Bundle {
+    let hp = player_max_health(stats);
+    (
+        Name::new("Player Unit"),
+        Health { current: hp, max: hp },
+        Defense { value: stats.defense },
+        UnitType::Human,
+        Team::Player,
+        CombatSlot(0),
+        StatusEffects::default(),
+    )
+}
+
+/// The weapons the player fights with. Unarmed: bare hands (slowed or sped up by gear like any weapon) so the fight can still end.
+pub fn player_weapons(stats: &CombatStats) -> Vec<WeaponStats> {
+    let mut weapons = stats.weapons.clone();
+    if weapons.is_empty() {
+        weapons.push(WeaponStats {
+            item_id: "Fists".to_string(),
+            inventory_index: usize::MAX,
+            damage: 1.0,
+            speed: stats.speed,
+            material: MaterialType::Flesh,
+            targeting: TargetingStrategy::FrontMost,
+            on_hit: Vec::new(),
+            ranged: None,
+            ammo: Vec::new(),
+        });
+    }
+    weapons
+}
+
+/// Display name for an item: its name, or the id when it isn't in the database.
+pub fn item_label(item_db: &crate::plugins::items::ItemDatabase, item_id: &str) -> String {
+    item_db.items.get(item_id).map_or(item_id.to_string(), |d| d.name.clone())
+}
+
+/// Combat components for one of `wielder`'s weapons.
+pub fn weapon_unit(wielder: Entity, weapon: &WeaponStats, label: String) -> impl Bundle {
+    (
+        Name::new(label.clone()),
+        WeaponOf(wielder),
+        WeaponLabel(label),
+        Attack { value: weapon.damage },
+        Speed { value: weapon.attack_speed() },
+        ActionMeter::default(),
+        weapon.material,
+        weapon.targeting,
+        OnHit(weapon.on_hit.clone()),
+        Team::Player,
+    )
+}
+
+/// Combat components for an enemy standing at `slot` in the enemy formation.
+pub fn enemy_unit(enemy: &EnemyDefinition, slot: u32) -> impl Bundle {
+    (
+        Name::new(enemy.name.clone()),
+        Enemy { id: enemy.id.clone() },
+        Health { current: enemy.health, max: enemy.health },
+        Attack { value: enemy.attack },
+        Defense { value: enemy.defense },
+        Speed { value: enemy.speed },
+        ActionMeter::default(),
+        enemy.unit_type,
+        enemy.material,
+        Team::Enemy,
+        enemy.targeting,
+        CombatSlot(slot),
+        OnHit(enemy.on_hit.clone()),
+        StatusEffects::default(),
+    )
+}
+
+/// Fallback enemy for nights with nothing in the roster.
+pub fn default_monster() -> EnemyDefinition {
+    EnemyDefinition {
+        id: "default_monster".to_string(),
+        name: "Enemy Monster".to_string(),
+        unit_type: UnitType::Monster,
+        material: MaterialType::Flesh,
+        health: 150.0,
+        attack: 15.0,
+        defense: 2.0,
+        speed: 10.0,
+        min_day: 1,
+        targeting: TargetingStrategy::default(),
+        on_hit: Vec::new(),
+        loot: Vec::new(),
+    }
+}
+
+type UnitUiQuery<'w, 's> = Query<'w, 's, (
+    &'static Name,
+    &'static Health,
+    &'static UnitType,
+    Option<&'static StatusEffects>,
+    &'static Children,
+), Or<(Changed<Health>, Changed<StatusEffects>)>>;
+
+/// Unit text: name, type, health and statuses. Action meters are bars, see `combat_fx`.
 fn update_combat_ui(
-    q_units: Query<(&Health, &UnitType, &ActionMeter, &Children)>,
-    mut q_text: Query<&mut Text>,
+    q_units: UnitUiQuery,
+    mut q_text: Query<&mut Text, Without<FloatingText>>,
 ) {
-    for (health, unit_type, meter, children) in q_units.iter() {
+    for (name, health, unit_type, statuses, children) in q_units.iter() {
         for &child in children.iter() {
             if let Ok(mut text) = q_text.get_mut(child) {
                 let type_name = match unit_type {
@@ -157,18 +414,49 @@ fn update_combat_ui(
                     UnitType::Monster => "Monster",
                     UnitType::Ethereal => "Ethereal",
                 };
-                **text = format!(
-                    "{}\nHP: {:.0}/{:.0}\nMeter: {:.0}%",
-                    type_name,
-                    health.current,
-                    health.max,
-                    (meter.value / meter.threshold * 100.0).clamp(0.0, 100.0)
-                );
+                **text = format!("{}\n{}\nHP: {:.0}/{:.0}", name, type_name, health.current.max(0.0), health.max);
+                for status in statuses.map_or(&[][..], |s| &s.active) {
+                    text.push_str(&format!("\n{:?} x{} ({})", status.kind, status.stacks, status.remaining_ticks));
+                }
             }
         }
     }
 }
 
+fn update_consumable_ui(
+    q_items: Query<(&Name, &Consumable, &Children), With<ConsumableOf>>,
+    mut q_text: Query<&mut Text>,
+) {
+    for (name, consumable, children) in q_items.iter() {
+        for &child in children.iter() {
+            if let Ok(mut text) = q_text.get_mut(child) {
+                **text = if consumable.is_spent() {
+                    format!("{}: used", name)
+                } else {
+                    format!("{} x{}", name, consumable.uses_left)
+                };
+            }
+        }
+    }
+}
+
+fn update_ammo_ui(
+    q_ammo: Query<(&Name, &Ammo, &Children), With<AmmoOf>>,
+    mut q_text: Query<&mut Text>,
+) {
+    for (name, ammo, children) in q_ammo.iter() {
+        for &child in children.iter() {
+            if let Ok(mut text) = q_text.get_mut(child) {
+                **text = format!("{} x{}", name, ammo.rounds_left);
+            }
+        }
+    }
+}
+
+pub(crate) fn meter_percent(meter: &ActionMeter) -> f32 {
+    (meter.value / meter.threshold * 100.0).clamp(0.0, 100.0)
+}
+
 #[derive(Component, Reflect, Default, Debug, Clone, Copy)]
 #[reflect(Component)]
 pub struct Health {
@@ -210,7 +498,7 @@ impl Default for ActionMeter {
     }
 }
 
-#[derive(Component, Reflect, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Component, Reflect, Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[reflect(Component)]
 pub enum MaterialType {
     #[default]
@@ -219,7 +507,17 @@ pub enum MaterialType {
     Flesh,
 }
 
-#[derive(Component, Reflect, Default, Debug, Clone, Copy, PartialEq, Eq)]
+impl From<crate::plugins::items::MaterialType> for MaterialType {
+    fn from(material: crate::plugins::items::MaterialType) -> Self {
+        match material {
+            crate::plugins::items::MaterialType::Steel => MaterialType::Steel,
+            crate::plugins::items::MaterialType::Silver => MaterialType::Silver,
+            crate::plugins::items::MaterialType::Flesh => MaterialType::Flesh,
+        }
+    }
+}
+
+#[derive(Component, Reflect, Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[reflect(Component)]
 pub enum UnitType {
     #[default]
@@ -266,59 +564,184 @@ pub fn calculate_damage(
     }
 }
 
-pub fn tick_timer_system(mut query: Query<(&Speed, &mut ActionMeter)>) {
-    for (speed, mut meter) in query.iter_mut() {
-        meter.value += speed.value;
+/// How a hit's damage came about, for the combat log.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DamageBreakdown {
+    pub base: f32,
+    pub material: MaterialType,
+    pub target_type: UnitType,
+    /// `material` efficiency against `target_type`
+    pub modifier: f32,
+    /// `base * modifier`, before defense
+    pub raw: f32,
+    pub defense: f32,
+    pub dealt: f32,
+}
+
+/// Same result as `calculate_damage`, with the intermediate values kept.
+pub fn damage_breakdown(
+    weapon_damage: f32,
+    material: MaterialType,
+    target_unit_type: UnitType,
+    target_defense: f32,
+) -> DamageBreakdown {
+    let modifier = material.efficiency(target_unit_type);
+    DamageBreakdown {
+        base: weapon_damage,
+        material,
+        target_type: target_unit_type,
+        modifier,
+        raw: weapon_damage * modifier,
+        defense: target_defense,
+        dealt: calculate_damage(weapon_damage, material, target_unit_type, target_defense),
     }
 }
 
-pub fn combat_turn_system(
-    mut commands: Commands,
-    mut q_units: Query<(Entity, &mut ActionMeter, &Attack, &Defense, &mut Health, &Team, &MaterialType, &UnitType)>,
-    mut next_state: ResMut<NextState<crate::plugins::core::GameState>>,
+/// Fills action meters. Speed buffs and Slow on a unit also apply to its weapons.
+pub fn tick_timer_system(
+    mut query: Query<(Entity, &Speed, &mut ActionMeter, Option<&WeaponOf>)>,
+    q_statuses: Query<&StatusEffects>,
 ) {
-    // Collect all potential targets first to avoid borrow checker issues with double iteration
-    let mut ready_units = Vec::new();
-    for (entity, meter, _, _, _, team, _, _) in q_units.iter() {
-        if meter.value >= meter.threshold {
-            ready_units.push((entity, *team));
-        }
+    for (entity, speed, mut meter, weapon_of) in query.iter_mut() {
+        let (bonus, factor) = q_statuses.get(weapon_of.map_or(entity, |w| w.0))
+            .map_or((0.0, 1.0), |s| (s.buff(StatType::Speed), s.speed_factor()));
+        meter.value += (speed.value + bonus).max(0.0) * factor;
     }
+}
 
-    for (attacker_entity, attacker_team) in ready_units {
-        let (attacker_damage, attacker_material) =
-            if let Ok((_, mut meter, attack, _, _, _, material, _)) = q_units.get_mut(attacker_entity) {
-                 if meter.value < meter.threshold { continue; }
-                 meter.value -= meter.threshold;
-                 (attack.value, *material)
-            } else {
-                continue;
-            };
-
-        // Find target
-        let mut target_entity_opt = None;
-        let mut target_defense_val = 0.0;
-        let mut target_unit_type_val = UnitType::Human;
-
-        for (candidate_entity, _, _, defense, health, team, _, unit_type) in q_units.iter() {
-            if *team != attacker_team && health.current > 0.0 {
-                target_entity_opt = Some(candidate_entity);
-                target_defense_val = defense.value;
-                target_unit_type_val = *unit_type;
-                break;
-            }
+/// A living enemy an attacker could hit.
+#[derive(Debug, Clone, Copy)]
+pub struct TargetCandidate {
+    pub entity: Entity,
+    pub health: f32,
+    pub threat: f32,
+    pub unit_type: UnitType,
+}
+
+/// Picks a target from `candidates`, which must be in formation order (front first).
+/// Ties go to the front-most candidate so the result only depends on the inputs and `rng`.
+pub fn choose_target(
+    strategy: TargetingStrategy,
+    material: MaterialType,
+    candidates: &[TargetCandidate],
+    rng: &mut impl Rng,
+) -> Option<Entity> {
+    // `min_by` keeps the first of equal elements
+    let chosen = match strategy {
+        TargetingStrategy::FrontMost => candidates.first(),
+        TargetingStrategy::LowestHealth => candidates.iter().min_by(|a, b| a.health.total_cmp(&b.health)),
+        TargetingStrategy::HighestThreat => candidates.iter().min_by(|a, b| b.threat.total_cmp(&a.threat)),
+        TargetingStrategy::Random => {
+            if candidates.is_empty() { None } else { Some(&candidates[rng.gen_range(0..candidates.len())]) }
         }
+        TargetingStrategy::MaterialEffective => candidates.iter().min_by(|a, b| {
+            material.efficiency(b.unit_type).total_cmp(&material.efficiency(a.unit_type))
+        }),
+    };
+    chosen.map(|c| c.entity)
+}
 
-        if let Some(target_entity) = target_entity_opt {
-             let damage = calculate_damage(attacker_damage, attacker_material, target_unit_type_val, target_defense_val);
+type AttackerQuery<'w, 's> = Query<'w, 's, (
+    Entity,
+    &'static mut ActionMeter,
+    &'static Attack,
+    &'static Speed,
+    &'static MaterialType,
+    &'static Team,
+    Option<&'static WeaponOf>,
+    Option<&'static TargetingStrategy>,
+    Option<&'static OnHit>,
+    Option<&'static AmmoSupply>,
+)>;
+
+type CombatUnitQuery<'w, 's> = Query<'w, 's, (
+    Entity,
+    &'static Defense,
+    &'static mut Health,
+    &'static Team,
+    &'static UnitType,
+    &'static CombatSlot,
+    Option<&'static mut StatusEffects>,
+)>;
 
-             info!("Unit {:?} ({:?}) attacks {:?} for {:.1} damage!", attacker_entity, attacker_team, target_entity, damage);
+pub fn combat_turn_system(
+    mut q_attackers: AttackerQuery,
+    mut q_units: CombatUnitQuery,
+    mut rng: ResMut<CombatRng>,
+    mut winner: ResMut<CombatWinner>,
+    mut ev_combat: EventWriter<CombatEvent>,
+) {
+    // Fight already decided, waiting for the arena to close
+    if winner.0.is_some() { return; }
+
+    // Collect ready attackers first to avoid borrow checker issues with double iteration.
+    // Sorted so attack order doesn't depend on query iteration order.
+    let mut ready_attackers = Vec::new();
+    // Unit -> damage per tick of everything it attacks with
+    let mut threat: HashMap<Entity, f32> = HashMap::new();
+    for (entity, meter, attack, speed, _, _, weapon_of, _, _, _) in q_attackers.iter() {
+        if meter.value >= meter.threshold {
+            ready_attackers.push(entity);
+        }
+        *threat.entry(weapon_of.map_or(entity, |w| w.0)).or_default() += attack.value * speed.value / meter.threshold;
+    }
+    ready_attackers.sort();
+
+    for attacker_entity in ready_attackers {
+        let Ok((_, mut meter, attack, _, material, team, weapon_of, own_strategy, on_hit, ammo)) = q_attackers.get_mut(attacker_entity) else {
+            continue;
+        };
+        if meter.value < meter.threshold { continue; }
+
+        // Weapons only swing while their wielder is alive
+        let wielder = weapon_of.map_or(attacker_entity, |w| w.0);
+        let Ok((_, _, wielder_health, _, _, _, wielder_statuses)) = q_units.get(wielder) else { continue; };
+        if wielder_health.current <= 0.0 { continue; }
+        let strategy = own_strategy.copied().unwrap_or_default();
+        // Attack buffs and Weaken on the wielder, and a ranged weapon shooting without ammo
+        let (damage_bonus, damage_factor) = wielder_statuses
+            .map_or((0.0, 1.0), |s| (s.buff(StatType::Attack), s.damage_factor()));
+        let damage_factor = damage_factor * ammo.map_or(1.0, |a| a.damage_factor());
+
+        meter.value -= meter.threshold;
+        let (attacker_damage, attacker_material, attacker_team) = ((attack.value + damage_bonus) * damage_factor, *material, *team);
+        let on_hit = on_hit.map(|h| h.0.clone()).unwrap_or_default();
+
+        // Find target among living enemies, front of the formation first
+        let mut candidates: Vec<(CombatSlot, TargetCandidate)> = q_units.iter()
+            .filter(|(_, _, health, team, _, _, _)| **team != attacker_team && health.current > 0.0)
+            .map(|(entity, _, health, _, unit_type, slot, _)| (*slot, TargetCandidate {
+                entity,
+                health: health.current,
+                threat: threat.get(&entity).copied().unwrap_or(0.0),
+                unit_type: *unit_type,
+            }))
+            .collect();
+        candidates.sort_by_key(|(slot, c)| (*slot, c.entity));
+        let candidates: Vec<TargetCandidate> = candidates.into_iter().map(|(_, c)| c).collect();
+
+        if let Some(target_entity) = choose_target(strategy, attacker_material, &candidates, &mut rng.rng) {
+             let Ok((_, defense, mut health, _, unit_type, _, statuses)) = q_units.get_mut(target_entity) else { continue; };
+             let defense_bonus = statuses.as_ref().map_or(0.0, |s| s.buff(StatType::Defense));
+             let hit = damage_breakdown(attacker_damage, attacker_material, *unit_type, defense.value + defense_bonus);
+             let damage = hit.dealt;
+
+             info!("Unit {:?} ({:?}, {:?}) attacks {:?} for {:.1} damage!", attacker_entity, attacker_team, attacker_material, target_entity, damage);
+
+             health.current -= damage;
+             ev_combat.send(CombatEvent::Attack { attacker: attacker_entity, target: target_entity, hit, remaining_health: health.current });
+             if health.current <= 0.0 {
+                 info!("Unit {:?} died!", target_entity);
+                 // The dead stay in the arena at 0 HP so the UI can fade them out; nothing targets them
+                 ev_combat.send(CombatEvent::Death { unit: target_entity });
+                 continue;
+             }
 
-             if let Ok((_, _, _, _, mut health, _, _, _)) = q_units.get_mut(target_entity) {
-                 health.current -= damage;
-                 if health.current <= 0.0 {
-                     info!("Unit {:?} died!", target_entity);
-                     commands.entity(target_entity).despawn_recursive();
+             let Some(mut statuses) = statuses else { continue; };
+             for status in &on_hit {
+                 if rng.rng.gen::<f32>() < status.chance {
+                     statuses.apply(status.kind, status.stacks, status.duration);
+                     ev_combat.send(CombatEvent::StatusApplied { target: target_entity, kind: status.kind, stacks: status.stacks });
                  }
              }
         }
@@ -328,7 +751,7 @@ pub fn combat_turn_system(
     let mut player_alive = false;
     let mut enemy_alive = false;
 
-    for (_, _, _, _, health, team, _, _) in q_units.iter() {
+    for (_, _, health, team, _, _, _) in q_units.iter() {
         if health.current > 0.0 {
             match team {
                 Team::Player => player_alive = true,
@@ -338,11 +761,16 @@ pub fn combat_turn_system(
     }
 
     if !player_alive {
-        info!("Player Defeated! Returning to City...");
-        next_state.set(crate::plugins::core::GameState::DayPhase);
+        info!("Player Defeated!");
+        winner.0 = Some(Team::Enemy);
     } else if !enemy_alive {
-        info!("Victory! Returning to City...");
-        next_state.set(crate::plugins::core::GameState::DayPhase);
+        info!("Victory!");
+        winner.0 = Some(Team::Player);
+    }
+
+    // The outcome plugin leaves the arena once the last effects have played
+    if let Some(team) = winner.0 {
+        ev_combat.send(CombatEvent::Victory { winner: team });
     }
 }
 
@@ -396,6 +824,117 @@ mod tests {
         assert_eq!(calculated, 3.2);
     }
 
+    #[test]
+    fn test_weapons_attack_with_own_material() {
+        let mut world = World::new();
+        world.init_resource::<CombatWinner>();
+        world.init_resource::<Events<CombatEvent>>();
+        world.insert_resource(CombatRng::from_seed(0));
+
+        let player = world.spawn((
+            Health { current: 100.0, max: 100.0 },
+            Defense { value: 0.0 },
+            UnitType::Human,
+            Team::Player,
+            CombatSlot(0),
+        )).id();
+        let ready = ActionMeter { value: 1000.0, threshold: 1000.0 };
+        let speed = Speed { value: 10.0 };
+        world.spawn((WeaponOf(player), Attack { value: 10.0 }, speed, ready, MaterialType::Silver, Team::Player));
+        world.spawn((WeaponOf(player), Attack { value: 10.0 }, speed, ActionMeter::default(), MaterialType::Steel, Team::Player));
+        let monster = world.spawn((
+            Health { current: 100.0, max: 100.0 },
+            Defense { value: 0.0 },
+            UnitType::Monster,
+            Team::Enemy,
+            CombatSlot(0),
+        )).id();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(combat_turn_system);
+        schedule.run(&mut world);
+
+        // Only the ready silver weapon swung: Raw = 10 * 2.0 = 20, Final = 2 * 20 - 0 = 40
+        assert_eq!(world.get::<Health>(monster).unwrap().current, 60.0);
+    }
+
+    #[test]
+    fn test_weakened_hit_inflicts_status() {
+        let mut world = World::new();
+        world.init_resource::<CombatWinner>();
+        world.init_resource::<Events<CombatEvent>>();
+        world.insert_resource(CombatRng::from_seed(0));
+
+        let mut weakened = StatusEffects::default();
+        weakened.apply(StatusKind::Weaken, 5, 100);
+        let player = world.spawn((
+            Health { current: 100.0, max: 100.0 },
+            Defense { value: 0.0 },
+            UnitType::Human,
+            Team::Player,
+            CombatSlot(0),
+            weakened,
+        )).id();
+        let bleed = crate::plugins::items::StatusApplication { kind: StatusKind::Bleed, stacks: 2, duration: 50, chance: 1.0 };
+        world.spawn((
+            WeaponOf(player),
+            Attack { value: 10.0 },
+            Speed { value: 10.0 },
+            ActionMeter { value: 1000.0, threshold: 1000.0 },
+            MaterialType::Flesh,
+            Team::Player,
+            OnHit(vec![bleed]),
+        ));
+        let monster = world.spawn((
+            Health { current: 100.0, max: 100.0 },
+            Defense { value: 0.0 },
+            UnitType::Monster,
+            Team::Enemy,
+            CombatSlot(0),
+            StatusEffects::default(),
+        )).id();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(combat_turn_system);
+        schedule.run(&mut world);
+
+        // Half damage from 5 Weaken stacks: Raw = 5 * 1.2 = 6, Final = 2 * 6 = 12
+        assert_eq!(world.get::<Health>(monster).unwrap().current, 88.0);
+        assert_eq!(world.get::<StatusEffects>(monster).unwrap().stacks(StatusKind::Bleed), 2);
+    }
+
+    #[test]
+    fn test_target_choice_per_strategy() {
+        let candidate = |index, health, threat, unit_type| TargetCandidate {
+            entity: Entity::from_raw(index),
+            health,
+            threat,
+            unit_type,
+        };
+        // Formation order: front first
+        let candidates = [
+            candidate(0, 80.0, 1.0, UnitType::Human),
+            candidate(1, 20.0, 5.0, UnitType::Monster),
+            candidate(2, 20.0, 5.0, UnitType::Ethereal),
+        ];
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut pick = |strategy, material| choose_target(strategy, material, &candidates, &mut rng).map(|e| e.index());
+
+        assert_eq!(pick(TargetingStrategy::FrontMost, MaterialType::Steel), Some(0));
+        // Ties go to the front-most
+        assert_eq!(pick(TargetingStrategy::LowestHealth, MaterialType::Steel), Some(1));
+        assert_eq!(pick(TargetingStrategy::HighestThreat, MaterialType::Steel), Some(1));
+        assert_eq!(pick(TargetingStrategy::MaterialEffective, MaterialType::Silver), Some(2));
+        assert_eq!(pick(TargetingStrategy::MaterialEffective, MaterialType::Steel), Some(0));
+
+        let random = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..8).map(|_| choose_target(TargetingStrategy::Random, MaterialType::Steel, &candidates, &mut rng)).collect::<Vec<_>>()
+        };
+        assert_eq!(random(9), random(9));
+        assert_eq!(choose_target(TargetingStrategy::Random, MaterialType::Steel, &[], &mut rng), None);
+    }
+
     #[test]
     fn test_action_meter_tick() {
         let mut app = App::new();
@@ -0,0 +1,252 @@
+use bevy::color::{Alpha, Mix};
+use bevy::prelude::*;
+use crate::plugins::combat::{meter_percent, ActionMeter, CombatEvent};
+use crate::plugins::core::GameState;
+
+/// How long a damage number floats before it disappears.
+pub const FLOAT_TEXT_SECONDS: f32 = 0.9;
+/// How far a damage number rises, in pixels.
+pub const FLOAT_TEXT_RISE: f32 = 40.0;
+pub const HIT_FLASH_SECONDS: f32 = 0.15;
+pub const DEATH_FADE_SECONDS: f32 = 0.6;
+/// Opacity a dead unit's box fades down to.
+pub const DEAD_ALPHA: f32 = 0.25;
+
+/// Arena effects: floating damage numbers, hit flashes, death fades and action meter bars.
+/// Everything except the bars is driven by `CombatEvent`s, so replays get the same effects.
+pub struct CombatFxPlugin;
+
+impl Plugin for CombatFxPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (
+                spawn_meter_bars,
+                combat_fx_events,
+                update_meter_bars,
+                float_text_system,
+                hit_flash_system,
+                death_fade_system,
+            ).chain().run_if(in_state(GameState::NightPhase)));
+    }
+}
+
+/// A damage or heal number rising from a unit.
+#[derive(Component, Debug)]
+pub struct FloatingText {
+    pub timer: Timer,
+}
+
+/// Brief white flash on a unit that was hit. `base` is the colour it returns to.
+#[derive(Component, Debug)]
+pub struct HitFlash {
+    pub timer: Timer,
+    pub base: Color,
+}
+
+/// A dead unit's box fading out. It stays dimmed once the timer is done.
+#[derive(Component, Debug)]
+pub struct DeathFade {
+    pub timer: Timer,
+    pub background: Color,
+    pub border: Color,
+}
+
+/// Fill of the action meter bar belonging to the entity inside.
+#[derive(Component, Debug)]
+pub struct MeterBar(pub Entity);
+
+/// Colour of a damage number for a hit with this material efficiency:
+/// grey when immune, blue when resisted, white when neutral, yellow to red the more effective.
+pub fn efficiency_color(modifier: f32) -> Color {
+    if modifier <= 0.0 {
+        Color::srgb(0.5, 0.5, 0.5)
+    } else if modifier < 1.0 {
+        Color::srgb(0.6, 0.7, 1.0)
+    } else if modifier == 1.0 {
+        Color::WHITE
+    } else if modifier < 2.0 {
+        Color::srgb(1.0, 0.85, 0.2)
+    } else {
+        Color::srgb(1.0, 0.35, 0.1)
+    }
+}
+
+fn floating_text(text: String, color: Color) -> impl Bundle {
+    (
+        Text::new(text),
+        TextFont { font_size: 20.0, ..default() },
+        TextColor(color),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(0.0),
+            right: Val::Px(8.0),
+            ..default()
+        },
+        ZIndex(5),
+        FloatingText { timer: Timer::from_seconds(FLOAT_TEXT_SECONDS, TimerMode::Once) },
+    )
+}
+
+/// Gives every arena node with an action meter a bar underneath its text.
+fn spawn_meter_bars(mut commands: Commands, q_new: Query<Entity, (Added<ActionMeter>, With<Node>)>) {
+    for entity in q_new.iter() {
+        commands.entity(entity).with_children(|p| {
+            p.spawn((
+                Node {
+                    width: Val::Px(120.0),
+                    height: Val::Px(6.0),
+                    margin: UiRect::top(Val::Px(2.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.1, 0.1, 0.1)),
+            ))
+            .with_child((
+                Node { width: Val::Percent(0.0), height: Val::Percent(100.0), ..default() },
+                BackgroundColor(Color::srgb(0.9, 0.8, 0.2)),
+                MeterBar(entity),
+            ));
+        });
+    }
+}
+
+fn update_meter_bars(mut q_bars: Query<(&MeterBar, &mut Node)>, q_meters: Query<&ActionMeter>) {
+    for (bar, mut node) in q_bars.iter_mut() {
+        if let Ok(meter) = q_meters.get(bar.0) {
+            node.width = Val::Percent(meter_percent(meter));
+        }
+    }
+}
+
+/// Turns combat events into effects on the affected unit's node.
+fn combat_fx_events(
+    mut commands: Commands,
+    mut ev_combat: EventReader<CombatEvent>,
+    q_colors: Query<(&BackgroundColor, Option<&BorderColor>)>,
+    mut q_flashes: Query<&mut HitFlash>,
+) {
+    for event in ev_combat.read() {
+        match event {
+            CombatEvent::Attack { target, hit, .. } => {
+                let Some(mut target_commands) = commands.get_entity(*target) else { continue; };
+                let text = if hit.modifier <= 0.0 { "Immune".to_string() } else { format!("{:.0}", hit.dealt) };
+                target_commands.with_child(floating_text(text, efficiency_color(hit.modifier)));
+
+                if let Ok(mut flash) = q_flashes.get_mut(*target) {
+                    flash.timer.reset();
+                } else if let Ok((background, _)) = q_colors.get(*target) {
+                    target_commands.insert(HitFlash {
+                        timer: Timer::from_seconds(HIT_FLASH_SECONDS, TimerMode::Once),
+                        base: background.0,
+                    });
+                }
+            }
+            CombatEvent::StatusDamage { unit, damage, .. } => {
+                if let Some(mut unit_commands) = commands.get_entity(*unit) {
+                    unit_commands.with_child(floating_text(format!("{:.0}", damage), Color::srgb(0.8, 0.2, 0.4)));
+                }
+            }
+            CombatEvent::Healed { unit, amount, .. } => {
+                if let Some(mut unit_commands) = commands.get_entity(*unit) {
+                    unit_commands.with_child(floating_text(format!("+{:.0}", amount), Color::srgb(0.3, 1.0, 0.3)));
+                }
+            }
+            CombatEvent::Death { unit } => {
+                let Ok((background, border)) = q_colors.get(*unit) else { continue; };
+                // Fade from the real colour, not from the middle of a flash
+                let background = q_flashes.get(*unit).map_or(background.0, |flash| flash.base);
+                let border = border.map_or(Color::NONE, |b| b.0);
+                if let Some(mut unit_commands) = commands.get_entity(*unit) {
+                    unit_commands.remove::<HitFlash>().insert(DeathFade {
+                        timer: Timer::from_seconds(DEATH_FADE_SECONDS, TimerMode::Once),
+                        background,
+                        border,
+                    });
+                }
+            }
+            CombatEvent::StatusApplied { .. }
+            | CombatEvent::ConsumableUsed { .. }
+            | CombatEvent::OutOfAmmo { .. }
+            | CombatEvent::Victory { .. } => {}
+        }
+    }
+}
+
+fn float_text_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_texts: Query<(Entity, &mut FloatingText, &mut Node, &mut TextColor)>,
+) {
+    for (entity, mut floating, mut node, mut color) in q_texts.iter_mut() {
+        floating.timer.tick(time.delta());
+        if floating.timer.finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        let progress = floating.timer.fraction();
+        node.top = Val::Px(-FLOAT_TEXT_RISE * progress);
+        color.0.set_alpha(1.0 - progress);
+    }
+}
+
+fn hit_flash_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_flashes: Query<(Entity, &mut HitFlash, &mut BackgroundColor)>,
+) {
+    for (entity, mut flash, mut background) in q_flashes.iter_mut() {
+        flash.timer.tick(time.delta());
+        background.0 = Color::WHITE.mix(&flash.base, flash.timer.fraction());
+        if flash.timer.finished() {
+            commands.entity(entity).remove::<HitFlash>();
+        }
+    }
+}
+
+fn death_fade_system(
+    time: Res<Time>,
+    mut q_fading: Query<(&mut DeathFade, &mut BackgroundColor, Option<&mut BorderColor>)>,
+) {
+    for (mut fade, mut background, border) in q_fading.iter_mut() {
+        if fade.timer.finished() { continue; }
+        fade.timer.tick(time.delta());
+        let alpha = 1.0 - (1.0 - DEAD_ALPHA) * fade.timer.fraction();
+        background.0 = fade.background.with_alpha(fade.background.alpha() * alpha);
+        if let Some(mut border) = border {
+            border.0 = fade.border.with_alpha(fade.border.alpha() * alpha);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::combat::{damage_breakdown, MaterialType, UnitType};
+
+    #[test]
+    fn test_events_spawn_effects() {
+        let mut world = World::new();
+        world.init_resource::<Events<CombatEvent>>();
+
+        let base = Color::srgb(0.5, 0.2, 0.2);
+        let ghost = world.spawn((Node::default(), BackgroundColor(base))).id();
+        let attacker = world.spawn_empty().id();
+
+        let hit = damage_breakdown(10.0, MaterialType::Silver, UnitType::Ethereal, 0.0);
+        world.send_event(CombatEvent::Attack { attacker, target: ghost, hit, remaining_health: 0.0 });
+        world.send_event(CombatEvent::Death { unit: ghost });
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(combat_fx_events);
+        schedule.run(&mut world);
+
+        // Silver on a ghost is very effective
+        let mut texts = world.query_filtered::<(&Text, &TextColor), With<FloatingText>>();
+        let (text, color) = texts.single(&world);
+        assert_eq!(text.0, format!("{:.0}", hit.dealt));
+        assert_eq!(color.0, efficiency_color(hit.modifier));
+        assert!(hit.modifier >= 2.0);
+
+        // Death replaces the flash, fading from the unflashed colour
+        assert!(world.get::<HitFlash>(ghost).is_none());
+        assert_eq!(world.get::<DeathFade>(ghost).unwrap().background, base);
+    }
+}
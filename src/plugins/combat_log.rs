@@ -0,0 +1,499 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use crate::plugins::combat::{
+    CombatEvent, CombatUnitUi, DamageBreakdown, Health, Team, UnitType, WeaponLabel, WeaponOf,
+};
+use crate::plugins::core::GameState;
+use crate::plugins::status_effects::StatusKind;
+
+/// Where the last live fight is written, and where the replay reads from.
+pub const COMBAT_LOG_FILE: &str = "last_fight.json";
+
+/// Scrolling log panel in the arena, JSON dump after each fight, and replay (F10 during the day,
+/// Space to step). Recording itself is part of `combat_rules`.
+pub struct CombatLogPlugin;
+
+impl Plugin for CombatLogPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::NightPhase), (
+                spawn_log_panel,
+                spawn_replay_arena.run_if(resource_exists::<CombatReplay>),
+            ))
+           .add_systems(OnExit(GameState::NightPhase), (
+                save_combat_log.run_if(not(resource_exists::<CombatReplay>)),
+                end_replay,
+            ).chain())
+           .add_systems(Update, start_replay_input.run_if(in_state(GameState::DayPhase)))
+           .add_systems(Update, (
+                replay_step_system.run_if(resource_exists::<CombatReplay>),
+                update_log_panel,
+            ).chain().run_if(in_state(GameState::NightPhase)));
+    }
+}
+
+/// A unit or weapon that took part in a fight. Log events refer to units by index in `CombatLog::units`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoggedUnit {
+    pub name: String,
+    pub team: Team,
+    /// `None` for weapons
+    #[serde(default)]
+    pub unit_type: Option<UnitType>,
+    #[serde(default)]
+    pub max_health: f32,
+    /// Index of the unit holding this weapon
+    #[serde(default)]
+    pub wielder: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CombatLogEvent {
+    Attack { attacker: usize, target: usize },
+    Damage { target: usize, hit: DamageBreakdown, remaining_health: f32 },
+    StatusApplied { target: usize, kind: StatusKind, stacks: u32 },
+    StatusDamage { unit: usize, kind: StatusKind, damage: f32, remaining_health: f32 },
+    ConsumableUsed { unit: usize, item: String },
+    OutOfAmmo { weapon: usize },
+    Healed { unit: usize, amount: f32, remaining_health: f32 },
+    Death { unit: usize },
+    Victory { winner: Team },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CombatLogEntry {
+    pub tick: u32,
+    pub event: CombatLogEvent,
+}
+
+/// Everything that happened in the current (or last) fight, in order.
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CombatLog {
+    /// `CombatRng` seed; with the same inventory and day it reproduces the encounter and the fight
+    #[serde(default)]
+    pub seed: u64,
+    pub units: Vec<LoggedUnit>,
+    pub entries: Vec<CombatLogEntry>,
+    /// Combat ticks run so far
+    pub ticks: u32,
+    #[serde(skip)]
+    indices: HashMap<Entity, usize>,
+}
+
+impl CombatLog {
+    pub fn with_seed(seed: u64) -> Self {
+        Self { seed, ..default() }
+    }
+
+    /// Adds a unit and returns its index.
+    pub fn register(&mut self, entity: Entity, unit: LoggedUnit) -> usize {
+        let index = self.units.len();
+        self.units.push(unit);
+        self.indices.insert(entity, index);
+        index
+    }
+
+    pub fn index_of(&self, entity: Entity) -> Option<usize> {
+        self.indices.get(&entity).copied()
+    }
+
+    pub fn unit_name(&self, index: usize) -> &str {
+        self.units.get(index).map_or("?", |u| u.name.as_str())
+    }
+
+    /// One readable line for the arena panel.
+    pub fn describe(&self, entry: &CombatLogEntry) -> String {
+        let text = match &entry.event {
+            CombatLogEvent::Attack { attacker, target } => {
+                format!("{} attacks {}", self.unit_name(*attacker), self.unit_name(*target))
+            }
+            CombatLogEvent::Damage { target, hit, remaining_health } => format!(
+                "{} takes {:.1} ({:.0} x{:.1} {:?} vs {:?} = {:.1}, defense {:.0}), {:.0} HP left",
+                self.unit_name(*target), hit.dealt, hit.base, hit.modifier, hit.material,
+                hit.target_type, hit.raw, hit.defense, remaining_health.max(0.0)
+            ),
+            CombatLogEvent::StatusApplied { target, kind, stacks } => {
+                format!("{} gains {} {:?}", self.unit_name(*target), stacks, kind)
+            }
+            CombatLogEvent::StatusDamage { unit, kind, damage, remaining_health } => format!(
+                "{} takes {:.2} from {:?}, {:.0} HP left",
+                self.unit_name(*unit), damage, kind, remaining_health.max(0.0)
+            ),
+            CombatLogEvent::ConsumableUsed { unit, item } => format!("{} uses {}", self.unit_name(*unit), item),
+            CombatLogEvent::Healed { unit, amount, remaining_health } => format!(
+                "{} heals {:.0}, {:.0} HP left", self.unit_name(*unit), amount, remaining_health
+            ),
+            CombatLogEvent::OutOfAmmo { weapon } => format!("{} is out of ammo", self.unit_name(*weapon)),
+            CombatLogEvent::Death { unit } => format!("{} dies", self.unit_name(*unit)),
+            CombatLogEvent::Victory { winner } => match winner {
+                Team::Player => "Victory!".to_string(),
+                Team::Enemy => "Defeat...".to_string(),
+            },
+        };
+        format!("[{:>4}] {}", entry.tick, text)
+    }
+
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(path, json)
+    }
+
+    pub fn load_from_file(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        serde_json::from_str(&text).map_err(std::io::Error::other)
+    }
+}
+
+type NewCombatUnitQuery<'w, 's> =
+    Query<'w, 's, (Entity, &'static Name, &'static Team, Option<&'static Health>, Option<&'static UnitType>, Option<&'static WeaponOf>), Added<Team>>;
+
+/// Adds newly spawned combat units to the log. Units are registered before weapons
+/// so a weapon's wielder always has an index.
+pub fn register_combat_units(
+    q_new: NewCombatUnitQuery,
+    mut log: ResMut<CombatLog>,
+) {
+    let mut new_units: Vec<_> = q_new.iter().collect();
+    new_units.sort_by_key(|(entity, _, _, _, _, weapon_of)| (weapon_of.is_some(), *entity));
+
+    for (entity, name, team, health, unit_type, weapon_of) in new_units {
+        let wielder = weapon_of.and_then(|w| log.index_of(w.0));
+        log.register(entity, LoggedUnit {
+            name: name.to_string(),
+            team: *team,
+            unit_type: unit_type.copied(),
+            max_health: health.map_or(0.0, |h| h.max),
+            wielder,
+        });
+    }
+}
+
+/// Turns this tick's `CombatEvent`s into log entries.
+pub fn record_combat_events(mut ev_combat: EventReader<CombatEvent>, mut log: ResMut<CombatLog>) {
+    log.ticks += 1;
+    let tick = log.ticks;
+
+    for event in ev_combat.read() {
+        let entries = match event.clone() {
+            CombatEvent::Attack { attacker, target, hit, remaining_health } => {
+                let (Some(attacker), Some(target)) = (log.index_of(attacker), log.index_of(target)) else {
+                    warn!("Combat event for an unregistered unit: {:?}", event);
+                    continue;
+                };
+                vec![
+                    CombatLogEvent::Attack { attacker, target },
+                    CombatLogEvent::Damage { target, hit, remaining_health },
+                ]
+            }
+            CombatEvent::StatusApplied { target, kind, stacks } => {
+                let Some(target) = log.index_of(target) else { continue; };
+                vec![CombatLogEvent::StatusApplied { target, kind, stacks }]
+            }
+            CombatEvent::StatusDamage { unit, kind, damage, remaining_health } => {
+                let Some(unit) = log.index_of(unit) else { continue; };
+                vec![CombatLogEvent::StatusDamage { unit, kind, damage, remaining_health }]
+            }
+            CombatEvent::ConsumableUsed { unit, item } => {
+                let Some(unit) = log.index_of(unit) else { continue; };
+                vec![CombatLogEvent::ConsumableUsed { unit, item }]
+            }
+            CombatEvent::Healed { unit, amount, remaining_health } => {
+                let Some(unit) = log.index_of(unit) else { continue; };
+                vec![CombatLogEvent::Healed { unit, amount, remaining_health }]
+            }
+            CombatEvent::OutOfAmmo { weapon } => {
+                let Some(weapon) = log.index_of(weapon) else { continue; };
+                vec![CombatLogEvent::OutOfAmmo { weapon }]
+            }
+            CombatEvent::Death { unit } => {
+                let Some(unit) = log.index_of(unit) else { continue; };
+                vec![CombatLogEvent::Death { unit }]
+            }
+            CombatEvent::Victory { winner } => vec![CombatLogEvent::Victory { winner }],
+        };
+        log.entries.extend(entries.into_iter().map(|event| CombatLogEntry { tick, event }));
+    }
+}
+
+fn save_combat_log(log: Res<CombatLog>) {
+    match log.save_to_file(COMBAT_LOG_FILE) {
+        Ok(()) => info!("Combat log saved to {}", COMBAT_LOG_FILE),
+        Err(e) => error!("Failed to save combat log: {}", e),
+    }
+}
+
+// ============================================================================
+// LOG PANEL
+// ============================================================================
+
+#[derive(Component)]
+pub struct CombatLogPanel;
+
+fn spawn_log_panel(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            right: Val::Px(10.0),
+            bottom: Val::Px(10.0),
+            width: Val::Px(420.0),
+            height: Val::Px(180.0),
+            flex_direction: FlexDirection::Column,
+            padding: UiRect::all(Val::Px(6.0)),
+            overflow: Overflow::scroll_y(),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+        ScrollPosition::default(),
+        CombatLogPanel,
+        CombatUnitUi, // Cleaned up with the arena
+    ));
+}
+
+/// Appends lines for new log entries and keeps the panel scrolled to the newest one.
+fn update_log_panel(
+    mut commands: Commands,
+    log: Res<CombatLog>,
+    mut q_panel: Query<(Entity, &mut ScrollPosition, Option<&Children>), With<CombatLogPanel>>,
+) {
+    if !log.is_changed() { return; }
+    let Ok((panel, mut scroll, children)) = q_panel.get_single_mut() else { return; };
+
+    let shown = children.map_or(0, |c| c.len());
+    if shown >= log.entries.len() { return; }
+
+    commands.entity(panel).with_children(|p| {
+        for entry in &log.entries[shown..] {
+            p.spawn((
+                Text::new(log.describe(entry)),
+                TextFont { font_size: 12.0, ..default() },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            ));
+        }
+    });
+    // Clamped to the content height by the layout
+    scroll.offset_y = f32::MAX;
+}
+
+// ============================================================================
+// REPLAY
+// ============================================================================
+
+/// Present while a saved fight is being replayed instead of a live one.
+#[derive(Resource)]
+pub struct CombatReplay {
+    pub recording: CombatLog,
+    /// Next entry of `recording` to apply
+    pub next: usize,
+    /// Unit index -> arena entity
+    entities: Vec<Entity>,
+    /// Attacker of the last `Attack` entry, for the `Damage` entry that follows it
+    attacker: Option<Entity>,
+}
+
+impl CombatReplay {
+    pub fn new(recording: CombatLog) -> Self {
+        Self { recording, next: 0, entities: Vec::new(), attacker: None }
+    }
+}
+
+fn start_replay_input(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !input.just_pressed(KeyCode::F10) { return; }
+
+    match CombatLog::load_from_file(COMBAT_LOG_FILE) {
+        Ok(recording) => {
+            info!("Replaying {} ({} entries). Press Space to step.", COMBAT_LOG_FILE, recording.entries.len());
+            commands.insert_resource(CombatReplay::new(recording));
+            next_state.set(GameState::NightPhase);
+        }
+        Err(e) => error!("Failed to load {}: {}", COMBAT_LOG_FILE, e),
+    }
+}
+
+/// Rebuilds the arena from the recorded units, at full health.
+fn spawn_replay_arena(mut commands: Commands, mut replay: ResMut<CombatReplay>) {
+    commands.insert_resource(CombatLog { seed: replay.recording.seed, units: replay.recording.units.clone(), ..default() });
+
+    let root = commands.spawn((
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            justify_content: JustifyContent::SpaceEvenly,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.05, 0.0, 0.1)),
+        CombatUnitUi,
+    )).id();
+    let column = |commands: &mut Commands| {
+        commands.spawn(Node { flex_direction: FlexDirection::Column, row_gap: Val::Px(10.0), ..default() })
+            .set_parent(root)
+            .id()
+    };
+    let (player_column, enemy_column) = (column(&mut commands), column(&mut commands));
+
+    let mut entities = Vec::new();
+    for unit in &replay.recording.units {
+        let entity = match unit.wielder.and_then(|w| entities.get(w).copied()) {
+            Some(wielder) => commands.spawn((
+                    Node { margin: UiRect::top(Val::Px(4.0)), ..default() },
+                    Name::new(unit.name.clone()),
+                    WeaponOf(wielder),
+                    WeaponLabel(unit.name.clone()),
+                    unit.team,
+                ))
+                .with_child((
+                    Text::new(unit.name.clone()),
+                    TextFont { font_size: 12.0, ..default() },
+                    TextColor(Color::srgb(0.8, 0.8, 1.0)),
+                ))
+                .set_parent(wielder)
+                .id(),
+            None => {
+                let (border, background, parent) = match unit.team {
+                    Team::Player => (Color::srgb(0.0, 0.0, 1.0), Color::srgb(0.2, 0.2, 0.5), player_column),
+                    Team::Enemy => (Color::srgb(1.0, 0.0, 0.0), Color::srgb(0.5, 0.2, 0.2), enemy_column),
+                };
+                commands.spawn((
+                    Node {
+                        width: Val::Px(200.0),
+                        padding: UiRect::all(Val::Px(8.0)),
+                        border: UiRect::all(Val::Px(2.0)),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BorderColor(border),
+                    BackgroundColor(background),
+                    Name::new(unit.name.clone()),
+                    Health { current: unit.max_health, max: unit.max_health },
+                    unit.unit_type.unwrap_or_default(),
+                    unit.team,
+                ))
+                .with_child((
+                    Text::new(""),
+                    TextFont { font_size: 16.0, ..default() },
+                    TextColor(Color::WHITE),
+                ))
+                .set_parent(parent)
+                .id()
+            }
+        };
+        entities.push(entity);
+    }
+    replay.entities = entities;
+}
+
+/// Space applies the next recorded entry; once the fight is over it returns to the city.
+/// Hits, deaths and heals are re-sent as `CombatEvent`s so the arena effects play as in a live fight.
+fn replay_step_system(
+    input: Res<ButtonInput<KeyCode>>,
+    mut replay: ResMut<CombatReplay>,
+    mut log: ResMut<CombatLog>,
+    mut q_health: Query<&mut Health>,
+    mut ev_combat: EventWriter<CombatEvent>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !input.just_pressed(KeyCode::Space) { return; }
+
+    let Some(entry) = replay.recording.entries.get(replay.next).cloned() else {
+        next_state.set(GameState::DayPhase);
+        return;
+    };
+    replay.next += 1;
+
+    let replay = &mut *replay;
+    let entity = |index: usize| replay.entities.get(index).copied();
+    let mut set_health = |unit: Entity, remaining_health: f32| {
+        if let Ok(mut health) = q_health.get_mut(unit) {
+            health.current = remaining_health;
+        }
+    };
+    match entry.event.clone() {
+        CombatLogEvent::Attack { attacker, .. } => {
+            replay.attacker = entity(attacker);
+        }
+        CombatLogEvent::Damage { target, hit, remaining_health } => {
+            if let Some(target) = entity(target) {
+                set_health(target, remaining_health);
+                if let Some(attacker) = replay.attacker {
+                    ev_combat.send(CombatEvent::Attack { attacker, target, hit, remaining_health });
+                }
+            }
+        }
+        CombatLogEvent::StatusDamage { unit, kind, damage, remaining_health } => {
+            if let Some(unit) = entity(unit) {
+                set_health(unit, remaining_health);
+                ev_combat.send(CombatEvent::StatusDamage { unit, kind, damage, remaining_health });
+            }
+        }
+        CombatLogEvent::Healed { unit, amount, remaining_health } => {
+            if let Some(unit) = entity(unit) {
+                set_health(unit, remaining_health);
+                ev_combat.send(CombatEvent::Healed { unit, amount, remaining_health });
+            }
+        }
+        CombatLogEvent::Death { unit } => {
+            if let Some(unit) = entity(unit) {
+                ev_combat.send(CombatEvent::Death { unit });
+            }
+        }
+        CombatLogEvent::StatusApplied { .. }
+        | CombatLogEvent::ConsumableUsed { .. }
+        | CombatLogEvent::OutOfAmmo { .. }
+        | CombatLogEvent::Victory { .. } => {}
+    }
+    log.ticks = entry.tick;
+    log.entries.push(entry);
+}
+
+fn end_replay(mut commands: Commands) {
+    commands.remove_resource::<CombatReplay>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::combat::{MaterialType, UnitType};
+    use crate::plugins::combat_sim::simulate_combat;
+    use crate::plugins::enemies::EnemyDefinition;
+    use crate::plugins::items::ItemDatabase;
+    use crate::plugins::metagame::{PersistentInventory, SavedItem};
+
+    #[test]
+    fn test_log_round_trips_through_json() {
+        let db = ItemDatabase::shipped();
+        let inventory = PersistentInventory {
+            items: vec![SavedItem::new("silver_dagger", 0, 0, 0)],
+        };
+        let ghoul = EnemyDefinition {
+            name: "Ghoul".to_string(),
+            attack: 2.0,
+            defense: 1.0,
+            ..EnemyDefinition::test("ghoul", UnitType::Monster, 30.0)
+        };
+        let log = simulate_combat(&inventory, &db, &[ghoul], 1).log;
+
+        // Units first, then weapons pointing at their wielder
+        let names: Vec<&str> = log.units.iter().map(|u| u.name.as_str()).collect();
+        assert_eq!(names, ["Player Unit", "Ghoul", "Silver Dagger"]);
+        assert_eq!(log.units[2].wielder, Some(0));
+
+        let hit = log.entries.iter().find_map(|e| match &e.event {
+            CombatLogEvent::Damage { target: 1, hit, .. } => Some(*hit),
+            _ => None,
+        }).unwrap();
+        assert_eq!((hit.material, hit.modifier, hit.defense), (MaterialType::Silver, 2.0, 1.0));
+
+        let json = serde_json::to_string(&log).unwrap();
+        let loaded: CombatLog = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.seed, 1);
+        assert_eq!(loaded.units, log.units);
+        assert_eq!(loaded.entries, log.entries);
+        assert_eq!(loaded.describe(loaded.entries.last().unwrap()), log.describe(log.entries.last().unwrap()));
+    }
+}
@@ -0,0 +1,126 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use crate::plugins::ammo::{ammo_supply, ammo_unit};
+use crate::plugins::combat::{
+    combat_rules, enemy_unit, item_label, player_unit, player_weapons, weapon_unit,
+    CombatEvent, CombatRng, CombatWinner, Team,
+};
+use crate::plugins::consumables::{consumable_unit, spent_inventory_indices, Consumable};
+use crate::plugins::combat_log::CombatLog;
+use crate::plugins::enemies::EnemyDefinition;
+use crate::plugins::inventory_utils::calculate_combat_stats;
+use crate::plugins::items::ItemDatabase;
+use crate::plugins::metagame::PersistentInventory;
+
+/// Ticks after which a fight nobody can win (e.g. steel against ethereal) is called a draw.
+pub const MAX_SIMULATION_TICKS: u32 = 100_000;
+
+/// Outcome of a headless fight.
+#[derive(Debug, Clone)]
+pub struct SimulationResult {
+    /// `None` if the fight hit `MAX_SIMULATION_TICKS` without a winner
+    pub winner: Option<Team>,
+    pub ticks: u32,
+    /// Everything that happened, the same log the arena records
+    pub log: CombatLog,
+    /// Indices into `PersistentInventory::items` of consumables used up during the fight
+    pub spent_items: Vec<usize>,
+}
+
+/// Runs a whole fight without a window: the same units the arena spawns, driven by the
+/// same `combat_rules`, one schedule run per tick. Identical inputs and seed give identical results.
+pub fn simulate_combat(
+    inventory: &PersistentInventory,
+    item_db: &ItemDatabase,
+    enemies: &[EnemyDefinition],
+    seed: u64,
+) -> SimulationResult {
+    let mut world = World::new();
+    world.init_resource::<CombatWinner>();
+    world.init_resource::<Events<CombatEvent>>();
+    world.insert_resource(CombatLog::with_seed(seed));
+    world.insert_resource(CombatRng::from_seed(seed));
+
+    let stats = calculate_combat_stats(inventory, item_db);
+    let player = world.spawn(player_unit(&stats)).id();
+    let ammo_entities: HashMap<usize, Entity> = stats.ammo.iter()
+        .map(|ammo| (ammo.inventory_index, world.spawn(ammo_unit(player, ammo, item_label(item_db, &ammo.item_id))).id()))
+        .collect();
+    for weapon in player_weapons(&stats) {
+        let mut entity = world.spawn(weapon_unit(player, &weapon, item_label(item_db, &weapon.item_id)));
+        if let Some(supply) = ammo_supply(&weapon, &ammo_entities) {
+            entity.insert(supply);
+        }
+    }
+    for consumable in &stats.consumables {
+        world.spawn(consumable_unit(player, consumable, item_label(item_db, &consumable.item_id)));
+    }
+    for (slot, enemy) in enemies.iter().enumerate() {
+        world.spawn(enemy_unit(enemy, slot as u32));
+    }
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems(combat_rules());
+
+    let mut ticks = 0;
+    while ticks < MAX_SIMULATION_TICKS && world.resource::<CombatWinner>().0.is_none() {
+        ticks += 1;
+        schedule.run(&mut world);
+        // What a frame would do; the log has already read this tick's events
+        world.resource_mut::<Events<CombatEvent>>().update();
+    }
+
+    let spent_items = spent_inventory_indices(world.query::<&Consumable>().iter(&world));
+    SimulationResult {
+        winner: world.resource::<CombatWinner>().0,
+        ticks,
+        spent_items,
+        log: world.remove_resource::<CombatLog>().unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::combat::UnitType;
+    use crate::plugins::combat_log::CombatLogEvent;
+    use crate::plugins::metagame::SavedItem;
+
+    fn sword_inventory() -> PersistentInventory {
+        PersistentInventory {
+            items: vec![SavedItem::new("steel_sword", 0, 0, 0)],
+        }
+    }
+
+    #[test]
+    fn test_simulation_is_deterministic() {
+        let db = ItemDatabase::shipped();
+        let lineup = vec![EnemyDefinition::test("ghoul", UnitType::Monster, 60.0), EnemyDefinition::test("bandit", UnitType::Human, 40.0)];
+
+        let first = simulate_combat(&sword_inventory(), &db, &lineup, 3);
+        let second = simulate_combat(&sword_inventory(), &db, &lineup, 3);
+
+        assert_eq!(first.winner, Some(Team::Player));
+        assert_eq!(first.log.seed, 3);
+        assert_eq!(first.ticks, second.ticks);
+        assert_eq!(first.log.ticks, first.ticks);
+        assert_eq!(first.log.entries, second.log.entries);
+        assert_eq!(first.log.entries.last().map(|e| &e.event), Some(&CombatLogEvent::Victory { winner: Team::Player }));
+        let deaths = first.log.entries.iter().filter(|e| matches!(e.event, CombatLogEvent::Death { .. })).count();
+        assert_eq!(deaths, 2);
+    }
+
+    #[test]
+    fn test_steel_cannot_hurt_ethereal() {
+        let db = ItemDatabase::shipped();
+        let result = simulate_combat(&sword_inventory(), &db, &[EnemyDefinition::test("wraith", UnitType::Ethereal, 50.0)], 0);
+
+        assert_eq!(result.winner, Some(Team::Enemy));
+        // Every player swing did nothing
+        let damage_to_wraith: f32 = result.log.entries.iter().filter_map(|e| match &e.event {
+            CombatLogEvent::Damage { target, hit, .. } if result.log.unit_name(*target) == "wraith" => Some(hit.dealt),
+            _ => None,
+        }).sum();
+        assert_eq!(damage_to_wraith, 0.0);
+    }
+}
@@ -0,0 +1,182 @@
+use bevy::prelude::*;
+use crate::plugins::combat::{CombatEvent, CombatWinner, Health};
+use crate::plugins::inventory_utils::ConsumableStats;
+use crate::plugins::items::{ConsumableEffect, ConsumableTrigger, StatType};
+use crate::plugins::metagame::PersistentInventory;
+use crate::plugins::status_effects::StatusEffects;
+
+/// A consumable carried into the fight by the unit in `ConsumableOf`.
+#[derive(Component, Debug, Clone)]
+pub struct Consumable {
+    pub item_id: String,
+    /// Index into `PersistentInventory::items`
+    pub inventory_index: usize,
+    pub trigger: ConsumableTrigger,
+    pub effects: Vec<ConsumableEffect>,
+    pub uses_left: u32,
+    pub potency: f32,
+    /// Combat ticks seen so far
+    pub elapsed: u32,
+}
+
+impl Consumable {
+    pub fn is_spent(&self) -> bool {
+        self.uses_left == 0
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ConsumableOf(pub Entity);
+
+/// Combat components for one of `holder`'s consumables.
+pub fn consumable_unit(holder: Entity, consumable: &ConsumableStats, label: String) -> impl Bundle {
+    (
+        Name::new(label),
+        ConsumableOf(holder),
+        Consumable {
+            item_id: consumable.item_id.clone(),
+            inventory_index: consumable.inventory_index,
+            trigger: consumable.definition.trigger,
+            effects: consumable.definition.effects.clone(),
+            uses_left: consumable.definition.uses,
+            potency: consumable.potency,
+            elapsed: 0,
+        },
+    )
+}
+
+/// Fires consumables whose trigger is met, one use at a time, in spawn order.
+/// Runs after statuses tick and before attacks.
+pub fn consumable_system(
+    mut q_items: Query<(Entity, &Name, &mut Consumable, &ConsumableOf)>,
+    mut q_holders: Query<(&mut Health, &mut StatusEffects)>,
+    winner: Res<CombatWinner>,
+    mut ev_combat: EventWriter<CombatEvent>,
+) {
+    if winner.0.is_some() { return; }
+
+    let mut order: Vec<Entity> = q_items.iter().map(|(entity, _, _, _)| entity).collect();
+    order.sort();
+
+    for entity in order {
+        let Ok((_, name, mut consumable, holder)) = q_items.get_mut(entity) else { continue; };
+        consumable.elapsed += 1;
+        if consumable.is_spent() { continue; }
+        let Ok((mut health, mut statuses)) = q_holders.get_mut(holder.0) else { continue; };
+        if health.current <= 0.0 { continue; }
+
+        let fires = match consumable.trigger {
+            ConsumableTrigger::HealthBelow { fraction } => health.current < health.max * fraction,
+            ConsumableTrigger::FightStart => consumable.elapsed == 1,
+            ConsumableTrigger::Every { ticks } => ticks > 0 && consumable.elapsed % ticks == 0,
+        };
+        if !fires { continue; }
+
+        consumable.uses_left -= 1;
+        ev_combat.send(CombatEvent::ConsumableUsed { unit: holder.0, item: name.to_string() });
+
+        for effect in &consumable.effects {
+            match *effect {
+                ConsumableEffect::Heal { amount } => {
+                    let before = health.current;
+                    health.current = (health.current + amount * consumable.potency).min(health.max);
+                    ev_combat.send(CombatEvent::Healed {
+                        unit: holder.0,
+                        amount: health.current - before,
+                        remaining_health: health.current,
+                    });
+                }
+                ConsumableEffect::Buff { stat, value, duration } => {
+                    // Health and Potency have nothing to buff mid-fight
+                    if matches!(stat, StatType::Attack | StatType::Defense | StatType::Speed) {
+                        statuses.add_buff(stat, value * consumable.potency, duration);
+                    }
+                }
+                ConsumableEffect::Cleanse => statuses.cleanse(),
+                ConsumableEffect::GainStatus { kind, stacks, duration } => {
+                    let stacks = ((stacks as f32 * consumable.potency).round() as u32).max(1);
+                    statuses.apply(kind, stacks, duration);
+                    ev_combat.send(CombatEvent::StatusApplied { target: holder.0, kind, stacks });
+                }
+            }
+        }
+    }
+}
+
+/// Inventory indices of consumables with no uses left.
+pub fn spent_inventory_indices<'a>(consumables: impl Iterator<Item = &'a Consumable>) -> Vec<usize> {
+    let mut spent: Vec<usize> = consumables.filter(|c| c.is_spent()).map(|c| c.inventory_index).collect();
+    spent.sort_unstable();
+    spent.dedup();
+    spent
+}
+
+/// Takes spent consumables out of the saved inventory once the fight is decided.
+/// Runs before the outcome is applied, while the indices still match the inventory the fight started with.
+pub(crate) fn remove_spent_consumables(
+    q_items: Query<&Consumable>,
+    winner: Res<CombatWinner>,
+    mut inventory: ResMut<PersistentInventory>,
+) {
+    if winner.0.is_none() { return; }
+    for index in spent_inventory_indices(q_items.iter()).into_iter().rev() {
+        let Some(consumable) = q_items.iter().find(|c| c.inventory_index == index) else { continue; };
+        // Only if the inventory still looks like it did when the fight started
+        if inventory.items.get(index).is_some_and(|item| item.item_id == consumable.item_id) {
+            info!("Used up {}", consumable.item_id);
+            inventory.items.remove(index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::items::ConsumableDefinition;
+    use crate::plugins::status_effects::StatusKind;
+
+    fn stats(trigger: ConsumableTrigger, effects: Vec<ConsumableEffect>, uses: u32, potency: f32) -> ConsumableStats {
+        ConsumableStats {
+            item_id: "potion".to_string(),
+            inventory_index: 0,
+            definition: ConsumableDefinition { trigger, effects, uses },
+            potency,
+        }
+    }
+
+    #[test]
+    fn test_consumables_fire_on_their_triggers() {
+        let mut world = World::new();
+        world.init_resource::<CombatWinner>();
+        world.init_resource::<Events<CombatEvent>>();
+
+        let holder = world.spawn((Health { current: 40.0, max: 100.0 }, StatusEffects::default())).id();
+        let heal = stats(ConsumableTrigger::HealthBelow { fraction: 0.5 }, vec![ConsumableEffect::Heal { amount: 20.0 }], 2, 1.5);
+        let buff = stats(
+            ConsumableTrigger::FightStart,
+            vec![
+                ConsumableEffect::Buff { stat: StatType::Attack, value: 4.0, duration: 10 },
+                ConsumableEffect::Cleanse,
+                ConsumableEffect::GainStatus { kind: StatusKind::Regeneration, stacks: 2, duration: 100 },
+            ],
+            1,
+            1.0,
+        );
+        let heal_item = world.spawn(consumable_unit(holder, &heal, "Potion".to_string())).id();
+        world.spawn(consumable_unit(holder, &buff, "Tonic".to_string()));
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(consumable_system);
+        schedule.run(&mut world);
+
+        // 20 * 1.5 potency brought health to 70, above the threshold, so only one use
+        assert_eq!(world.get::<Health>(holder).unwrap().current, 70.0);
+        assert_eq!(world.get::<Consumable>(heal_item).unwrap().uses_left, 1);
+        assert_eq!(world.get::<StatusEffects>(holder).unwrap().buff(StatType::Attack), 4.0);
+        assert_eq!(world.get::<StatusEffects>(holder).unwrap().stacks(StatusKind::Regeneration), 2);
+
+        schedule.run(&mut world);
+        let spent: Vec<usize> = spent_inventory_indices(world.query::<&Consumable>().iter(&world));
+        assert_eq!(spent, vec![0]);
+    }
+}
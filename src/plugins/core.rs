@@ -26,7 +26,6 @@ pub enum GameState {
    NightPhase,            // Auto-battle
    #[allow(dead_code)]
    EventResolution,       // Dialogs
-   #[allow(dead_code)]
    GameOver,
 }
 
@@ -0,0 +1,419 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use crate::plugins::core::GameState;
+use crate::plugins::inventory::{
+    orient_shape, rotate_shape, spawn_item_entity, Bag, GridPosition, InStorage, InventoryChangedEvent, InventoryGridContainer,
+    InventoryGridState, InventoryItem, ItemFlip, ItemRotation,
+};
+use crate::plugins::items::{ItemDatabase, RecipeDefinition};
+
+/// Combines adjacent recipe ingredients into the recipe result.
+/// Crafting happens on demand (Craft button or C key) during `EveningPhase`.
+pub struct CraftingPlugin;
+
+impl Plugin for CraftingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ReadyRecipes>()
+           .add_event::<CraftRequest>()
+           .add_event::<ItemCraftedEvent>()
+           .add_systems(OnEnter(GameState::EveningPhase), spawn_crafting_ui)
+           .add_systems(OnExit(GameState::EveningPhase), cleanup_crafting_ui)
+           .add_systems(Update, (
+               update_ready_recipes,
+               craft_input_system,
+               craft_system,
+           ).chain().run_if(in_state(GameState::EveningPhase)));
+    }
+}
+
+/// Ask the crafting system to craft the first recipe whose ingredients are ready.
+#[derive(Event)]
+pub struct CraftRequest;
+
+/// Sent after a recipe was crafted.
+#[derive(Event, Debug, Clone)]
+pub struct ItemCraftedEvent {
+    /// Index into `ItemDatabase::recipes`
+    pub recipe_index: usize,
+    pub result_id: String,
+    pub result_entity: Entity,
+    pub position: IVec2,
+    pub rotation: u8,
+    /// Item ids of the ingredients that were used up
+    pub consumed: Vec<String>,
+}
+
+/// Recipes whose full ingredient set (and catalysts) currently sit together on the grid.
+/// Recomputed every frame during `EveningPhase`; matches never share items.
+#[derive(Resource, Default, Debug)]
+pub struct ReadyRecipes {
+    pub matches: Vec<RecipeMatch>,
+}
+
+impl ReadyRecipes {
+    /// The match an entity belongs to, if any.
+    pub fn match_for(&self, entity: Entity) -> Option<&RecipeMatch> {
+        self.matches.iter().find(|m| m.ingredients.contains(&entity) || m.catalysts.contains(&entity))
+    }
+}
+
+#[derive(Component)]
+struct CraftingUiRoot;
+
+#[derive(Component)]
+struct CraftButton;
+
+/// Minimal view of an item on the grid, used by the recipe matcher.
+#[derive(Debug, Clone)]
+pub struct GridItemInfo {
+    pub entity: Entity,
+    pub item_id: String,
+    /// Absolute occupied cells
+    pub cells: Vec<IVec2>,
+}
+
+/// A set of grid items satisfying one recipe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecipeMatch {
+    pub recipe_index: usize,
+    /// Entities consumed by crafting
+    pub ingredients: Vec<Entity>,
+    /// Entities required but kept
+    pub catalysts: Vec<Entity>,
+}
+
+/// True if any cell of `a` touches any cell of `b` orthogonally.
+pub fn cells_adjacent(a: &[IVec2], b: &[IVec2]) -> bool {
+    a.iter().any(|ca| b.iter().any(|cb| (ca.x - cb.x).abs() + (ca.y - cb.y).abs() == 1))
+}
+
+/// Finds a connected group of items holding every ingredient and catalyst of `recipe`.
+/// Each item is used at most once, so duplicate ingredients need duplicate items.
+pub fn find_recipe_match(recipe_index: usize, recipe: &RecipeDefinition, items: &[GridItemInfo]) -> Option<RecipeMatch> {
+    if recipe.ingredients.is_empty() { return None; }
+
+    let mut needed: Vec<&str> = recipe.ingredients.iter().map(String::as_str).collect();
+    needed.extend(recipe.catalysts.iter().map(String::as_str));
+
+    // Try every item as the seed of the group
+    for (i, seed) in items.iter().enumerate() {
+        let Some(slot) = needed.iter().position(|id| *id == seed.item_id) else { continue; };
+        let mut remaining = needed.clone();
+        remaining.swap_remove(slot);
+
+        let mut chosen = vec![i];
+        if grow_group(items, &mut chosen, &mut remaining) {
+            return Some(split_roles(recipe_index, recipe, items, &chosen));
+        }
+    }
+    None
+}
+
+/// Finds every disjoint ready group on the grid, recipe by recipe.
+pub fn find_all_recipe_matches(recipes: &[RecipeDefinition], items: &[GridItemInfo]) -> Vec<RecipeMatch> {
+    let mut matches = Vec::new();
+    let mut available: Vec<GridItemInfo> = items.to_vec();
+
+    for (index, recipe) in recipes.iter().enumerate() {
+        while let Some(found) = find_recipe_match(index, recipe, &available) {
+            available.retain(|info| !found.ingredients.contains(&info.entity) && !found.catalysts.contains(&info.entity));
+            matches.push(found);
+        }
+    }
+    matches
+}
+
+/// Depth-first growth of `chosen` by adjacent items that fill a remaining requirement.
+fn grow_group(items: &[GridItemInfo], chosen: &mut Vec<usize>, remaining: &mut Vec<&str>) -> bool {
+    if remaining.is_empty() { return true; }
+
+    for (i, candidate) in items.iter().enumerate() {
+        if chosen.contains(&i) { continue; }
+        let Some(slot) = remaining.iter().position(|id| *id == candidate.item_id) else { continue; };
+        if !chosen.iter().any(|&c| cells_adjacent(&items[c].cells, &candidate.cells)) { continue; }
+
+        let id = remaining.swap_remove(slot);
+        chosen.push(i);
+        if grow_group(items, chosen, remaining) { return true; }
+        chosen.pop();
+        remaining.push(id);
+    }
+    false
+}
+
+/// Assigns the chosen items to ingredient or catalyst roles.
+fn split_roles(recipe_index: usize, recipe: &RecipeDefinition, items: &[GridItemInfo], chosen: &[usize]) -> RecipeMatch {
+    let mut ingredient_counts: HashMap<&str, usize> = HashMap::new();
+    for id in &recipe.ingredients {
+        *ingredient_counts.entry(id.as_str()).or_default() += 1;
+    }
+
+    let mut result = RecipeMatch { recipe_index, ingredients: Vec::new(), catalysts: Vec::new() };
+    for &i in chosen {
+        let item = &items[i];
+        match ingredient_counts.get_mut(item.item_id.as_str()) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                result.ingredients.push(item.entity);
+            }
+            _ => result.catalysts.push(item.entity),
+        }
+    }
+    result
+}
+
+// ============================================================================
+// SYSTEMS
+// ============================================================================
+
+fn spawn_crafting_ui(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            right: Val::Px(20.0),
+            bottom: Val::Px(50.0),
+            ..default()
+        },
+        CraftingUiRoot,
+    )).with_children(|parent| {
+        parent.spawn((
+            Button,
+            Node {
+                width: Val::Px(100.0),
+                height: Val::Px(40.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.6, 0.5, 0.1)),
+            CraftButton,
+        )).with_children(|btn| {
+            btn.spawn((
+                Text::new("Craft (C)"),
+                TextFont { font_size: 16.0, ..default() },
+                TextColor(Color::WHITE),
+                PickingBehavior::IGNORE,
+            ));
+        });
+    });
+}
+
+fn cleanup_crafting_ui(mut commands: Commands, q_root: Query<Entity, With<CraftingUiRoot>>) {
+    for e in q_root.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+}
+
+fn craft_input_system(
+    input: Res<ButtonInput<KeyCode>>,
+    q_button: Query<&Interaction, (Changed<Interaction>, With<CraftButton>)>,
+    mut ev_request: EventWriter<CraftRequest>,
+) {
+    let pressed = q_button.iter().any(|i| *i == Interaction::Pressed);
+    if pressed || input.just_pressed(KeyCode::KeyC) {
+        ev_request.send(CraftRequest);
+    }
+}
+
+type GridItemQuery<'w, 's> = Query<
+    'w, 's,
+    (Entity, &'static InventoryItem, &'static GridPosition, &'static ItemRotation, &'static ItemFlip, &'static Parent),
+    (Without<Bag>, Without<InStorage>),
+>;
+
+/// Items placed on the grid (shop previews and stored items excluded).
+fn collect_grid_items(q_items: &GridItemQuery, container: Entity) -> Vec<GridItemInfo> {
+    q_items.iter()
+        .filter(|(_, _, _, _, _, parent)| parent.get() == container)
+        .map(|(entity, item, pos, rot, flip, _)| GridItemInfo {
+            entity,
+            item_id: item.item_id.clone(),
+            cells: orient_shape(&item.base_shape, rot.0, flip.0).into_iter().map(|o| pos.0 + o).collect(),
+        })
+        .collect()
+}
+
+pub fn update_ready_recipes(
+    item_db: Res<ItemDatabase>,
+    q_items: GridItemQuery,
+    q_container: Query<Entity, With<InventoryGridContainer>>,
+    mut ready: ResMut<ReadyRecipes>,
+) {
+    let matches = match q_container.get_single() {
+        Ok(container) => find_all_recipe_matches(&item_db.recipes, &collect_grid_items(&q_items, container)),
+        Err(_) => Vec::new(),
+    };
+    if ready.matches != matches {
+        ready.matches = matches;
+    }
+}
+
+/// The inventory grid crafting rearranges.
+#[derive(SystemParam)]
+struct CraftGrid<'w, 's> {
+    container: Query<'w, 's, Entity, With<InventoryGridContainer>>,
+    state: ResMut<'w, InventoryGridState>,
+}
+
+/// Events sent once a craft went through.
+#[derive(SystemParam)]
+struct CraftEvents<'w> {
+    changed: EventWriter<'w, InventoryChangedEvent>,
+    crafted: EventWriter<'w, ItemCraftedEvent>,
+}
+
+fn craft_system(
+    mut commands: Commands,
+    mut ev_request: EventReader<CraftRequest>,
+    item_db: Res<ItemDatabase>,
+    ready: Res<ReadyRecipes>,
+    q_items: GridItemQuery,
+    mut grid: CraftGrid,
+    mut events: CraftEvents,
+) {
+    if ev_request.read().count() == 0 { return; }
+    let Ok(container) = grid.container.get_single() else { return; };
+    let grid_state = &mut *grid.state;
+
+    let Some(found) = ready.matches.first() else {
+        info!("Nothing to craft.");
+        return;
+    };
+    let items = collect_grid_items(&q_items, container);
+
+    let recipe = &item_db.recipes[found.recipe_index];
+    let Some(result_def) = item_db.items.get(&recipe.result) else {
+        warn!("Recipe result '{}' is not a known item", recipe.result);
+        return;
+    };
+
+    // Free the ingredient cells, then look for a spot for the result among them
+    let freed: Vec<IVec2> = items.iter()
+        .filter(|info| found.ingredients.contains(&info.entity))
+        .flat_map(|info| info.cells.iter().copied())
+        .collect();
+    for cell in &freed {
+        grid_state.occupancy.remove(cell);
+    }
+
+    let mut anchors = freed.clone();
+    anchors.sort_by_key(|c| (c.y, c.x));
+    let placement = anchors.iter()
+        .flat_map(|&pos| (0..4u8).map(move |rot| (pos, rot)))
+        .find(|&(pos, rot)| {
+            // Anchor must land on a freed cell so the result replaces its ingredients
+            rotate_shape(&result_def.shape, rot).iter().any(|o| freed.contains(&(pos + *o)))
+                && grid_state.can_place_item(&result_def.shape, pos, rot, None)
+        });
+
+    let Some((pos, rot)) = placement else {
+        // Restore occupancy; nothing was consumed
+        for info in items.iter().filter(|info| found.ingredients.contains(&info.entity)) {
+            for cell in &info.cells {
+                grid_state.occupancy.insert(*cell, info.entity);
+            }
+        }
+        info!("No room for crafted '{}'.", recipe.result);
+        return;
+    };
+
+    let consumed: Vec<String> = items.iter()
+        .filter(|info| found.ingredients.contains(&info.entity))
+        .map(|info| info.item_id.clone())
+        .collect();
+    for entity in &found.ingredients {
+        commands.entity(*entity).despawn_recursive();
+    }
+
+    let result_entity = spawn_item_entity(&mut commands, container, result_def, pos, rot, grid_state);
+    for offset in rotate_shape(&result_def.shape, rot) {
+        grid_state.occupancy.insert(pos + offset, result_entity);
+    }
+
+    info!("Crafted '{}' from {:?}", recipe.result, consumed);
+    events.changed.send(InventoryChangedEvent);
+    events.crafted.send(ItemCraftedEvent {
+        recipe_index: found.recipe_index,
+        result_id: recipe.result.clone(),
+        result_entity,
+        position: pos,
+        rotation: rot,
+        consumed,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(index: u32, id: &str, cells: &[(i32, i32)]) -> GridItemInfo {
+        GridItemInfo {
+            entity: Entity::from_raw(index),
+            item_id: id.to_string(),
+            cells: cells.iter().map(|&(x, y)| IVec2::new(x, y)).collect(),
+        }
+    }
+
+    #[test]
+    fn test_match_requires_adjacent_duplicates() {
+        let recipe = RecipeDefinition {
+            ingredients: vec!["health_potion".into(), "health_potion".into()],
+            result: "strong_health_potion".into(),
+            catalysts: vec![],
+        };
+
+        // Two potions, not touching
+        let apart = [info(0, "health_potion", &[(0, 0)]), info(1, "health_potion", &[(2, 0)])];
+        assert_eq!(find_recipe_match(0, &recipe, &apart), None);
+
+        // A single potion can't satisfy both ingredients
+        assert_eq!(find_recipe_match(0, &recipe, &apart[..1]), None);
+
+        let touching = [info(0, "health_potion", &[(0, 0)]), info(1, "health_potion", &[(1, 0)])];
+        let found = find_recipe_match(0, &recipe, &touching).unwrap();
+        assert_eq!(found.ingredients, vec![Entity::from_raw(0), Entity::from_raw(1)]);
+    }
+
+    #[test]
+    fn test_all_matches_are_disjoint() {
+        let recipe = RecipeDefinition {
+            ingredients: vec!["health_potion".into(), "health_potion".into()],
+            result: "strong_health_potion".into(),
+            catalysts: vec![],
+        };
+
+        // Row of three potions: only one pair can be ready
+        let row = [
+            info(0, "health_potion", &[(0, 0)]),
+            info(1, "health_potion", &[(1, 0)]),
+            info(2, "health_potion", &[(2, 0)]),
+        ];
+        assert_eq!(find_all_recipe_matches(std::slice::from_ref(&recipe), &row).len(), 1);
+
+        let mut square = row.to_vec();
+        square.push(info(3, "health_potion", &[(2, 1)]));
+        assert_eq!(find_all_recipe_matches(&[recipe], &square).len(), 2);
+    }
+
+    #[test]
+    fn test_catalyst_is_required_but_not_consumed() {
+        let recipe = RecipeDefinition {
+            ingredients: vec!["steel_sword".into(), "whetstone".into()],
+            result: "hero_sword".into(),
+            catalysts: vec!["unique_charm".into()],
+        };
+
+        let mut items = vec![
+            info(0, "steel_sword", &[(0, 0), (0, 1)]),
+            info(1, "whetstone", &[(1, 1)]),
+        ];
+        assert_eq!(find_recipe_match(0, &recipe, &items), None);
+
+        // Charm touches the whetstone, which makes the group connected
+        items.push(info(2, "unique_charm", &[(2, 1)]));
+        let found = find_recipe_match(0, &recipe, &items).unwrap();
+        assert_eq!(found.ingredients, vec![Entity::from_raw(0), Entity::from_raw(1)]);
+        assert_eq!(found.catalysts, vec![Entity::from_raw(2)]);
+    }
+}
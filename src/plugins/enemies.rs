@@ -0,0 +1,343 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use rand::Rng;
+use serde::Deserialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use crate::plugins::combat::{MaterialType, TargetingStrategy, UnitType};
+use crate::plugins::items::{load_items, load_ron_file, ron_files_in, ItemDatabase, ItemDatabaseReloaded, ItemLoadError, StatusApplication};
+
+/// Directory scanned for `*.ron` enemy files.
+pub const ENEMIES_ASSET_DIR: &str = "assets/enemies";
+
+/// Most enemies a single night encounter can spawn.
+pub const MAX_ENEMIES_PER_FIGHT: usize = 3;
+
+pub struct EnemiesPlugin;
+
+impl Plugin for EnemiesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EnemyDatabase>()
+           .add_systems(Startup, (load_enemies, validate_loot_on_startup.after(load_items)).chain())
+           .add_systems(Update, validate_loot_on_reload);
+    }
+}
+
+/// Marks a combat unit spawned from an `EnemyDefinition`.
+#[derive(Component, Debug, Clone)]
+pub struct Enemy {
+    pub id: String,
+}
+
+#[derive(Resource, Default, Debug)]
+pub struct EnemyDatabase {
+    pub enemies: HashMap<String, EnemyDefinition>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct EnemyDefinition {
+    pub id: String,
+    pub name: String,
+    pub unit_type: UnitType,
+    #[serde(default)]
+    pub material: MaterialType,
+    pub health: f32,
+    pub attack: f32,
+    #[serde(default)]
+    pub defense: f32,
+    pub speed: f32,
+    /// First day this enemy can show up
+    #[serde(default = "default_min_day")]
+    pub min_day: u32,
+    #[serde(default)]
+    pub targeting: TargetingStrategy,
+    /// Statuses inflicted on hit
+    #[serde(default)]
+    pub on_hit: Vec<StatusApplication>,
+    #[serde(default)]
+    pub loot: Vec<LootEntry>,
+}
+
+fn default_min_day() -> u32 {
+    1
+}
+
+/// Item dropped with probability `chance` (0..=1) when the enemy is defeated.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LootEntry {
+    pub item_id: String,
+    pub chance: f32,
+}
+
+/// Layout of a single enemy file.
+#[derive(Debug, Default, Deserialize)]
+pub struct EnemyFile {
+    #[serde(default)]
+    pub enemies: Vec<EnemyDefinition>,
+}
+
+#[derive(Debug)]
+pub enum EnemyLoadError {
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// RON syntax or schema error, with the id of the enemy it points into when known.
+    Parse {
+        path: PathBuf,
+        enemy_id: Option<String>,
+        source: Box<ron::error::SpannedError>,
+    },
+    DuplicateId {
+        id: String,
+        first: PathBuf,
+        second: PathBuf,
+    },
+    InvalidEnemy {
+        path: PathBuf,
+        id: String,
+        reason: String,
+    },
+}
+
+/// The shared RON helpers report in item terms; here every entry is an enemy.
+impl From<ItemLoadError> for EnemyLoadError {
+    fn from(e: ItemLoadError) -> Self {
+        match e {
+            ItemLoadError::Io { path, source } => EnemyLoadError::Io { path, source },
+            ItemLoadError::Parse { path, item_id, source } => EnemyLoadError::Parse { path, enemy_id: item_id, source },
+            ItemLoadError::DuplicateId { id, first, second } => EnemyLoadError::DuplicateId { id, first, second },
+            ItemLoadError::InvalidItem { path, id, reason } => EnemyLoadError::InvalidEnemy { path, id, reason },
+        }
+    }
+}
+
+impl fmt::Display for EnemyLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnemyLoadError::Io { path, source } => {
+                write!(f, "{}: {}", path.display(), source)
+            }
+            EnemyLoadError::Parse { path, enemy_id: Some(id), source } => {
+                write!(f, "{}: enemy '{}': {}", path.display(), id, source)
+            }
+            EnemyLoadError::Parse { path, enemy_id: None, source } => {
+                write!(f, "{}: {}", path.display(), source)
+            }
+            EnemyLoadError::DuplicateId { id, first, second } => {
+                write!(f, "{}: duplicate enemy id '{}' (first defined in {})", second.display(), id, first.display())
+            }
+            EnemyLoadError::InvalidEnemy { path, id, reason } => {
+                write!(f, "{}: enemy '{}': {}", path.display(), id, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EnemyLoadError {}
+
+impl EnemyDatabase {
+    /// Loads every `*.ron` file in `dir` (sorted by file name) into a fresh database.
+    pub fn load_from_dir(dir: impl AsRef<Path>) -> Result<Self, EnemyLoadError> {
+        let mut db = EnemyDatabase::default();
+        // Enemy id -> file it came from, for duplicate reporting
+        let mut sources: HashMap<String, PathBuf> = HashMap::new();
+
+        for path in ron_files_in(dir.as_ref())? {
+            let file: EnemyFile = load_ron_file(&path)?;
+            for enemy in file.enemies {
+                if enemy.health <= 0.0 {
+                    return Err(EnemyLoadError::InvalidEnemy { path, id: enemy.id, reason: "health must be positive".to_string() });
+                }
+                if let Some(first) = sources.get(&enemy.id) {
+                    return Err(EnemyLoadError::DuplicateId { id: enemy.id, first: first.clone(), second: path });
+                }
+                sources.insert(enemy.id.clone(), path.clone());
+                db.enemies.insert(enemy.id.clone(), enemy);
+            }
+        }
+
+        Ok(db)
+    }
+}
+
+/// A loot entry naming an item that isn't in the item database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownLootItem {
+    pub enemy_id: String,
+    pub item_id: String,
+}
+
+impl fmt::Display for UnknownLootItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "enemy '{}': loot '{}' is not a known item", self.enemy_id, self.item_id)
+    }
+}
+
+/// Checks every loot entry against the item database. Enemies are visited in id order so the report is stable.
+pub fn validate_enemy_loot(enemy_db: &EnemyDatabase, item_db: &ItemDatabase) -> Vec<UnknownLootItem> {
+    let mut enemies: Vec<&EnemyDefinition> = enemy_db.enemies.values().collect();
+    enemies.sort_by(|a, b| a.id.cmp(&b.id));
+    enemies.into_iter()
+        .flat_map(|enemy| enemy.loot.iter().map(move |entry| (enemy, entry)))
+        .filter(|(_, entry)| !item_db.items.contains_key(&entry.item_id))
+        .map(|(enemy, entry)| UnknownLootItem { enemy_id: enemy.id.clone(), item_id: entry.item_id.clone() })
+        .collect()
+}
+
+/// Logs loot entries that would drop items the game doesn't know.
+pub fn report_loot_issues(enemy_db: &EnemyDatabase, item_db: &ItemDatabase) {
+    let issues = validate_enemy_loot(enemy_db, item_db);
+    if issues.is_empty() {
+        info!("Enemy loot validation passed.");
+        return;
+    }
+    warn!("Enemy loot validation found {} problem(s):", issues.len());
+    for issue in &issues {
+        warn!("  {}", issue);
+    }
+}
+
+fn validate_loot_on_startup(enemy_db: Res<EnemyDatabase>, item_db: Res<ItemDatabase>) {
+    report_loot_issues(&enemy_db, &item_db);
+}
+
+/// Reloaded items may have dropped an id some loot still names.
+fn validate_loot_on_reload(
+    mut ev_reloaded: EventReader<ItemDatabaseReloaded>,
+    enemy_db: Res<EnemyDatabase>,
+    item_db: Res<ItemDatabase>,
+) {
+    if ev_reloaded.read().count() > 0 {
+        report_loot_issues(&enemy_db, &item_db);
+    }
+}
+
+#[cfg(test)]
+impl EnemyDatabase {
+    /// The enemy files shipped in `ENEMIES_ASSET_DIR`.
+    pub(crate) fn shipped() -> Self {
+        Self::load_from_dir(Path::new(env!("CARGO_MANIFEST_DIR")).join(ENEMIES_ASSET_DIR)).expect("enemy assets should load")
+    }
+}
+
+#[cfg(test)]
+impl EnemyDefinition {
+    /// A plain flesh enemy named after its id: 4 attack, 10 speed, no statuses or loot.
+    pub(crate) fn test(id: &str, unit_type: UnitType, health: f32) -> Self {
+        Self {
+            id: id.to_string(),
+            name: id.to_string(),
+            unit_type,
+            material: MaterialType::Flesh,
+            health,
+            attack: 4.0,
+            speed: 10.0,
+            min_day: 1,
+            ..Default::default()
+        }
+    }
+}
+
+impl EnemyDefinition {
+    /// Copy with health and attack scaled up for later days (+10% HP, +5% attack per day).
+    pub fn scaled_for_day(&self, day: u32) -> EnemyDefinition {
+        let days_past = day.saturating_sub(1) as f32;
+        EnemyDefinition {
+            health: self.health * (1.0 + 0.10 * days_past),
+            attack: self.attack * (1.0 + 0.05 * days_past),
+            ..self.clone()
+        }
+    }
+}
+
+/// Picks tonight's enemies: one more enemy every three days (up to `MAX_ENEMIES_PER_FIGHT`),
+/// drawn from those whose `min_day` has been reached, with stats scaled by day.
+pub fn pick_encounter(db: &EnemyDatabase, day: u32, rng: &mut impl Rng) -> Vec<EnemyDefinition> {
+    // Sorted so a seeded rng always gives the same lineup
+    let mut pool: Vec<&EnemyDefinition> = db.enemies.values().filter(|e| e.min_day <= day).collect();
+    pool.sort_by(|a, b| a.id.cmp(&b.id));
+    if pool.is_empty() { return Vec::new(); }
+
+    let count = (1 + day.saturating_sub(1) as usize / 3).min(MAX_ENEMIES_PER_FIGHT);
+    (0..count)
+        .map(|_| pool[rng.gen_range(0..pool.len())].scaled_for_day(day))
+        .collect()
+}
+
+fn load_enemies(mut enemy_db: ResMut<EnemyDatabase>) {
+    match EnemyDatabase::load_from_dir(ENEMIES_ASSET_DIR) {
+        Ok(db) => {
+            *enemy_db = db;
+            info!("EnemyDatabase loaded with {} enemies.", enemy_db.enemies.len());
+        }
+        Err(e) => error!("Failed to load enemy database: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn load_db() -> EnemyDatabase {
+        EnemyDatabase::shipped()
+    }
+
+    #[test]
+    fn test_encounter_scales_with_day() {
+        let db = load_db();
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let day_one = pick_encounter(&db, 1, &mut rng);
+        assert_eq!(day_one.len(), 1);
+        // Nothing past its min_day
+        assert!(day_one.iter().all(|e| e.min_day <= 1));
+
+        let day_seven = pick_encounter(&db, 7, &mut rng);
+        assert_eq!(day_seven.len(), 3);
+
+        let ghoul = &db.enemies["ghoul"];
+        assert_eq!(ghoul.scaled_for_day(3).health, ghoul.health * 1.2);
+        assert!(db.enemies.values().any(|e| e.unit_type == UnitType::Ethereal));
+    }
+
+    #[test]
+    fn test_loot_names_known_items() {
+        let mut db = load_db();
+        let item_db = ItemDatabase::shipped();
+        let issues = validate_enemy_loot(&db, &item_db);
+        assert!(issues.is_empty(), "unexpected issues: {:?}", issues);
+
+        db.enemies.get_mut("ghoul").unwrap().loot.push(LootEntry { item_id: "silver_dager".to_string(), chance: 0.5 });
+        assert_eq!(validate_enemy_loot(&db, &item_db), vec![
+            UnknownLootItem { enemy_id: "ghoul".to_string(), item_id: "silver_dager".to_string() },
+        ]);
+    }
+
+    #[test]
+    fn test_invalid_enemy_is_named_as_enemy() {
+        let dir = std::env::temp_dir().join(format!("cw_enemies_bad_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("night.ron"),
+            r#"(enemies: [(id: "ghoul", name: "Ghoul", unit_type: Monster, health: 0.0, attack: 1.0, speed: 1.0)])"#,
+        ).unwrap();
+
+        let err = EnemyDatabase::load_from_dir(&dir).unwrap_err();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(matches!(err, EnemyLoadError::InvalidEnemy { ref id, .. } if id == "ghoul"));
+        assert!(err.to_string().ends_with("enemy 'ghoul': health must be positive"), "{}", err);
+    }
+
+    #[test]
+    fn test_encounter_is_deterministic_for_seed() {
+        let db = load_db();
+        let ids = |seed| {
+            pick_encounter(&db, 10, &mut StdRng::seed_from_u64(seed)).into_iter().map(|e| e.id).collect::<Vec<_>>()
+        };
+        assert_eq!(ids(42), ids(42));
+    }
+}
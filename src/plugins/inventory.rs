@@ -1,7 +1,8 @@
 use bevy::prelude::*;
-use bevy::utils::HashMap;
+use bevy::utils::{HashMap, HashSet};
 use crate::plugins::core::GameState;
-use crate::plugins::items::{ItemDefinition, ItemType};
+use crate::plugins::items::{ItemDatabase, ItemDatabaseReloaded, ItemDefinition, ItemType};
+use crate::plugins::metagame::{PendingItems, PersistentInventory, SavedItem};
 
 /// Plugin managing all inventory logic, grid, and interaction.
 /// Implements "Inventory Tetris" mechanics using Bevy Observers.
@@ -13,17 +14,21 @@ impl Plugin for InventoryPlugin {
            // Resources: Single source of truth for grid topology
           .init_resource::<InventoryGridState>()
           .init_resource::<InteractionState>()
+          .init_resource::<StorageRule>()
            // Events: Signal changes for stat recalculation
           .add_event::<InventoryChangedEvent>()
            // UI Lifecycle Systems
-          .add_systems(OnEnter(GameState::EveningPhase), setup_inventory_ui)
-          .add_systems(OnExit(GameState::EveningPhase), cleanup_inventory)
+           // The grid is rebuilt from PersistentInventory on entry and written back on exit
+          .add_systems(OnEnter(GameState::EveningPhase), (setup_inventory_ui, restore_inventory_grid, rebuild_inventory_grid, unpack_pending_items).chain())
+          .add_systems(OnExit(GameState::EveningPhase), (save_inventory_grid, cleanup_inventory).chain())
            // Update Systems (run only in inventory phase)
           .add_systems(
                Update,
                (
                    update_drag_visuals,        // Visual validation (red/green)
                    update_item_transforms,     // Smooth snapping
+                   refresh_items_on_reload,    // Hot-reloaded definitions
+                   layout_item_cells.after(update_drag_visuals).after(refresh_items_on_reload), // Cell-by-cell shapes
                ).run_if(in_state(GameState::EveningPhase))
            )
            // Bevy Picking Observers: New event system for Drag & Drop (Bevy 0.15)
@@ -42,6 +47,17 @@ pub const CELL_GAP: f32 = 2.0;
 // Effective grid step for calculations (Size + Gap)
 pub const GRID_STEP: f32 = CELL_SIZE;
 
+// Storage strip: a block of cells at the right edge of the grid container where items can be parked.
+// Bags can't provide slots here.
+pub const STORAGE_COLUMN: i32 = 9;
+pub const STORAGE_WIDTH: i32 = 3;
+pub const STORAGE_HEIGHT: i32 = 9;
+
+/// Whether a cell lies in the storage strip.
+pub fn storage_contains(cell: IVec2) -> bool {
+   (STORAGE_COLUMN..STORAGE_COLUMN + STORAGE_WIDTH).contains(&cell.x) && (0..STORAGE_HEIGHT).contains(&cell.y)
+}
+
 // ============================================================================
 // COMPONENTS
 // ============================================================================
@@ -56,6 +72,11 @@ pub struct InventoryItem {
    pub height: u8,
 }
 
+/// One drawn cell of an item, a child of the item's node. Holds the cell's offset in the unrotated shape.
+/// Cells are the item's hit area; the item node itself is a transparent bounding box.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ItemCell(pub IVec2);
+
 /// Bag component. A bag is an item that PROVIDES slots.
 #[derive(Component)]
 pub struct Bag {
@@ -71,6 +92,10 @@ pub struct GridPosition(pub IVec2);
 #[derive(Component, Debug, Clone, Copy)]
 pub struct ItemRotation(pub u8);
 
+/// Whether the item is mirrored left to right. The mirror is applied before the rotation.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct ItemFlip(pub bool);
+
 /// Marker for items in "Storage" (Limbo), not on the grid.
 #[derive(Component)]
 pub struct InStorage;
@@ -83,6 +108,10 @@ pub struct InventoryUiRoot;
 #[derive(Component)]
 pub struct InventoryGridContainer;
 
+/// Marker for the storage strip drawn inside the grid container.
+#[derive(Component)]
+pub struct InventoryStorageStrip;
+
 // ============================================================================
 // RESOURCES
 // ============================================================================
@@ -96,6 +125,18 @@ pub struct InventoryGridState {
    pub slots: HashMap<IVec2, Entity>,
    /// Active zone bounds (to limit bag movement).
    pub bounds: IRect,
+   /// Storage map: Coordinate -> Item Entity parked in the storage strip
+   pub storage: HashMap<IVec2, Entity>,
+}
+
+/// What happens to items left in storage when the evening ends.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StorageRule {
+   /// Stored items stay parked until the next evening
+   #[default]
+   Keep,
+   /// Stored items are thrown away
+   Discard,
 }
 
 /// State of the current drag operation.
@@ -105,31 +146,44 @@ pub struct InteractionState {
    /// Original position (for revert on invalid drop)
    pub original_grid_pos: IVec2,
    pub original_rotation: u8,
+   pub original_flipped: bool,
    pub was_in_storage: bool,
 }
 
 #[derive(Event)]
 pub struct InventoryChangedEvent;
 
+pub type BagLayoutQuery<'w, 's> = Query<
+   'w, 's,
+   (Entity, &'static GridPosition, &'static ItemRotation, &'static ItemFlip, &'static Bag, Has<InStorage>),
+>;
+pub type ItemLayoutQuery<'w, 's> = Query<
+   'w, 's,
+   (Entity, &'static GridPosition, &'static ItemRotation, &'static ItemFlip, &'static InventoryItem, Has<InStorage>),
+   Without<Bag>,
+>;
+
 // ============================================================================
 // GRID ALGORITHMS CORE
 // ============================================================================
 
 impl InventoryGridState {
-   /// Full rebuild of slot and occupancy maps.
+   /// Full rebuild of slot, occupancy and storage maps.
    /// Called after any successful inventory change.
-   pub fn rebuild(
-       &mut self,
-       bags: &Query<(Entity, &GridPosition, &ItemRotation, &Bag)>,
-       items: &Query<(Entity, &GridPosition, &ItemRotation, &InventoryItem), Without<Bag>>,
-   ) {
+   pub fn rebuild(&mut self, bags: &BagLayoutQuery, items: &ItemLayoutQuery) {
        self.slots.clear();
        self.occupancy.clear();
+       self.storage.clear();
        self.bounds = IRect::new(0, 0, 0, 0);
 
        // 1. Project Bags onto grid (Create "Background" of slots)
-       for (entity, pos, rot, bag) in bags.iter() {
-           let shape = rotate_shape(&bag.provided_slots, rot.0);
+       for (entity, pos, rot, flip, bag, in_storage) in bags.iter() {
+           let shape = orient_shape(&bag.provided_slots, rot.0, flip.0);
+           // Stored bags provide nothing
+           if in_storage {
+               self.storage.extend(shape.into_iter().map(|offset| (pos.0 + offset, entity)));
+               continue;
+           }
            for offset in shape {
                let slot_pos = pos.0 + offset;
                // If slots overlap, last one wins (or logic to forbid could be added)
@@ -142,9 +196,12 @@ impl InventoryGridState {
        }
 
        // 2. Place Items (Fill "Foreground")
-       for (entity, pos, rot, item) in items.iter() {
-           // Items in storage are ignored (query filter should handle this, but adding check)
-           let shape = rotate_shape(&item.base_shape, rot.0);
+       for (entity, pos, rot, flip, item, in_storage) in items.iter() {
+           let shape = orient_shape(&item.base_shape, rot.0, flip.0);
+           if in_storage {
+               self.storage.extend(shape.into_iter().map(|offset| (pos.0 + offset, entity)));
+               continue;
+           }
            for offset in shape {
                let cell = pos.0 + offset;
 
@@ -188,7 +245,7 @@ impl InventoryGridState {
    }
 
    /// Checks if a BAG can be placed.
-   /// Rule: Bags must not overlap each other (in this implementation).
+   /// Rule: Bags must not overlap each other (in this implementation) or the storage strip.
    pub fn can_place_bag(
        &self,
        shape: &[IVec2],
@@ -199,6 +256,9 @@ impl InventoryGridState {
        let rotated = rotate_shape(shape, rot);
        for offset in rotated {
            let target = pos + offset;
+           if storage_contains(target) {
+               return false;
+           }
            // Check if anyone already provides a slot here
            if let Some(provider) = self.slots.get(&target) {
                if Some(*provider) != ignore_entity {
@@ -209,6 +269,101 @@ impl InventoryGridState {
        true
    }
 
+   /// Checks if an item (or empty bag) can be parked in the storage strip.
+   /// Stored items only need to stay inside the strip and off each other.
+   pub fn can_store_item(
+       &self,
+       shape: &[IVec2],
+       pos: IVec2,
+       rot: u8,
+       ignore_entity: Option<Entity>,
+   ) -> bool {
+       rotate_shape(shape, rot).into_iter().map(|offset| pos + offset).all(|cell| {
+           storage_contains(cell) && self.storage.get(&cell).is_none_or(|other| Some(*other) == ignore_entity)
+       })
+   }
+
+   /// First free spot (position, rotation) in the storage strip, scanning row by row.
+   pub fn find_storage_spot(&self, shape: &[IVec2]) -> Option<(IVec2, u8)> {
+       // Rotated shapes can reach left of or above their origin, so look a little past the strip
+       let margin = shape.iter().map(|o| o.x.abs().max(o.y.abs())).max().unwrap_or(0);
+       for y in -margin..STORAGE_HEIGHT {
+           for x in STORAGE_COLUMN - margin..STORAGE_COLUMN + STORAGE_WIDTH {
+               for rot in 0..4 {
+                   let pos = IVec2::new(x, y);
+                   if self.can_store_item(shape, pos, rot, None) {
+                       return Some((pos, rot));
+                   }
+               }
+           }
+       }
+       None
+   }
+
+   /// Checks a drop of the dragged item at `to` (position, rotation, flipped), coming from `from`.
+   /// Touching the storage strip means parking the item there; anything else is a grid placement.
+   pub fn can_drop(
+       &self,
+       entity: Entity,
+       shape: &[IVec2],
+       is_bag: bool,
+       from: (IVec2, u8, bool),
+       to: (IVec2, u8, bool),
+   ) -> bool {
+       let mirrored = mirror_shape(shape, to.2);
+       if drops_into_storage(&mirrored, (to.0, to.1)) {
+           // A bag holding items can't be parked, its contents would fall out
+           self.can_store_item(&mirrored, to.0, to.1, Some(entity)) && (!is_bag || self.bag_contents(entity).is_empty())
+       } else if is_bag {
+           self.can_move_bag(shape, entity, from, to)
+       } else {
+           self.can_place_item(&mirrored, to.0, to.1, Some(entity))
+       }
+   }
+
+   /// Items resting entirely on `bag`'s slots. These travel with the bag when it moves.
+   pub fn bag_contents(&self, bag: Entity) -> HashSet<Entity> {
+       let mut contents: HashSet<Entity> = self.occupancy.values().copied().collect();
+       for (cell, item) in &self.occupancy {
+           if self.slots.get(cell) != Some(&bag) {
+               contents.remove(item);
+           }
+       }
+       contents
+   }
+
+   /// Checks if a BAG can move from `from` to `to` (position, rotation, flipped) together with its contents.
+   /// Besides the usual bag rule, every item must still be supported afterwards and the
+   /// carried items must not land on anything else.
+   pub fn can_move_bag(
+       &self,
+       shape: &[IVec2],
+       bag: Entity,
+       from: (IVec2, u8, bool),
+       to: (IVec2, u8, bool),
+   ) -> bool {
+       let shape = mirror_shape(shape, to.2);
+       if !self.can_place_bag(&shape, to.0, to.1, Some(bag)) {
+           return false;
+       }
+
+       let contents = self.bag_contents(bag);
+       let slots: HashSet<IVec2> = self.slots.iter()
+           .filter(|(_, provider)| **provider != bag)
+           .map(|(cell, _)| *cell)
+           .chain(rotate_shape(&shape, to.1).into_iter().map(|offset| to.0 + offset))
+           .collect();
+
+       let mut occupancy: HashMap<IVec2, Entity> = HashMap::new();
+       for (cell, item) in &self.occupancy {
+           let cell = if contents.contains(item) { carried_cell(*cell, from, to) } else { *cell };
+           if !slots.contains(&cell) || occupancy.insert(cell, *item).is_some_and(|other| other != *item) {
+               return false;
+           }
+       }
+       true
+   }
+
     // Helper: Find a free spot for an item (Basic "First Fit" algorithm)
     // Used by Shop and initial loading
     pub fn find_free_spot(
@@ -259,6 +414,72 @@ pub fn rotate_shape(shape: &[IVec2], rot: u8) -> Vec<IVec2> {
    }).collect()
 }
 
+/// Mirrors a shape left to right around its (0,0) cell, or leaves it as is.
+/// Functions taking a shape and a rotation expect a flipped item's shape to be mirrored first.
+pub fn mirror_shape(shape: &[IVec2], flipped: bool) -> Vec<IVec2> {
+   if !flipped {
+       return shape.to_vec();
+   }
+   shape.iter().map(|p| IVec2::new(-p.x, p.y)).collect()
+}
+
+/// A shape's cells for an orientation: mirrored first (if flipped), then rotated.
+pub fn orient_shape(shape: &[IVec2], rot: u8, flipped: bool) -> Vec<IVec2> {
+   rotate_shape(&mirror_shape(shape, flipped), rot)
+}
+
+/// Bounding box of a shape after rotation, in offsets from the origin cell.
+/// `min` goes negative for most rotations, since shapes turn around their (0,0) cell.
+pub fn shape_bounds(shape: &[IVec2], rot: u8) -> IRect {
+   let rotated = rotate_shape(shape, rot);
+   let min = rotated.iter().copied().reduce(IVec2::min).unwrap_or(IVec2::ZERO);
+   let max = rotated.iter().copied().reduce(IVec2::max).unwrap_or(IVec2::ZERO);
+   IRect { min, max }
+}
+
+/// Pixel position of an item's node (the top-left of its rotated bounding box) when its origin cell is at `pos`.
+pub fn item_node_origin(pos: IVec2, shape: &[IVec2], rot: u8) -> Vec2 {
+   (pos + shape_bounds(shape, rot).min).as_vec2() * GRID_STEP
+}
+
+/// Grid position an item node's current pixel position snaps to.
+fn snapped_grid_pos(node: &Node, shape: &[IVec2], rot: u8) -> IVec2 {
+   let left = if let Val::Px(l) = node.left { l } else { 0.0 };
+   let top = if let Val::Px(t) = node.top { t } else { 0.0 };
+   // Round to nearest grid integer index, then step from the box corner to the origin cell
+   IVec2::new((left / GRID_STEP).round() as i32, (top / GRID_STEP).round() as i32) - shape_bounds(shape, rot).min
+}
+
+/// Whether an item dropped at `to` (position, rotation) lands in the storage strip.
+pub fn drops_into_storage(shape: &[IVec2], to: (IVec2, u8)) -> bool {
+   rotate_shape(shape, to.1).into_iter().any(|offset| storage_contains(to.0 + offset))
+}
+
+/// Change of orientation (turns, mirror) between two bag orientations: undo `from`, then apply `to`.
+/// Mirroring reverses the sense of rotation, so a mirror in between adds the old turns instead.
+fn carried_orientation(from: (IVec2, u8, bool), to: (IVec2, u8, bool)) -> (u8, bool) {
+   if from.2 == to.2 {
+       ((to.1 + 4 - from.1 % 4) % 4, false)
+   } else {
+       ((to.1 + from.1) % 4, true)
+   }
+}
+
+/// Where a grid point ends up when a bag moves from `from` to `to` (position, rotation, flipped).
+/// Points turn and mirror with the bag around its origin, the same pivot `rotate_shape` uses.
+pub fn carried_cell(cell: IVec2, from: (IVec2, u8, bool), to: (IVec2, u8, bool)) -> IVec2 {
+   let (turns, flipped) = carried_orientation(from, to);
+   to.0 + orient_shape(&[cell - from.0], turns, flipped)[0]
+}
+
+/// New position, rotation and flip of an item carried along by a bag move.
+pub fn carried_item(pos: IVec2, rot: u8, flipped: bool, from: (IVec2, u8, bool), to: (IVec2, u8, bool)) -> (IVec2, u8, bool) {
+   let (turns, mirror) = carried_orientation(from, to);
+   // A mirror on top of the item's own orientation runs its rotation backwards
+   let (rot, flipped) = if mirror { ((turns + 4 - rot % 4) % 4, !flipped) } else { ((rot + turns) % 4, flipped) };
+   (carried_cell(pos, from, to), rot, flipped)
+}
+
 pub use crate::plugins::inventory_utils::calculate_combat_stats;
 
 // ============================================================================
@@ -266,35 +487,48 @@ pub use crate::plugins::inventory_utils::calculate_combat_stats;
 // ============================================================================
 
 /// Start drag
+/// Pointer events bubble up from the cell that was hit, so the observers act on the item entity in the chain.
 fn on_drag_start(
    trigger: Trigger<Pointer<DragStart>>,
    mut commands: Commands,
-   q_items: Query<(Entity, &GridPosition, &ItemRotation, Has<InStorage>)>,
+   q_items: Query<(&GridPosition, &ItemRotation, &ItemFlip, Has<InStorage>)>,
+   q_cells: Query<(Entity, &Parent), With<ItemCell>>,
    mut interaction: ResMut<InteractionState>,
+   grid_state: Res<InventoryGridState>,
 ) {
    let entity = trigger.entity();
 
-   if let Ok((_, grid_pos, rot, in_storage)) = q_items.get(entity) {
+   if let Ok((grid_pos, rot, flip, in_storage)) = q_items.get(entity) {
        // 1. Save state for potential undo
        interaction.dragged_entity = Some(entity);
        interaction.original_grid_pos = grid_pos.0;
        interaction.original_rotation = rot.0;
+       interaction.original_flipped = flip.0;
        interaction.was_in_storage = in_storage;
 
        // 2. Visual feedback: Lift item to foreground (Z-Index)
        // Use large local Z-index. GlobalZIndex is better if available.
        commands.entity(entity).insert(ZIndex(100));
 
-       // 3. CRITICAL: Disable Picking for the item itself.
+       // 3. CRITICAL: Disable Picking for the item's cells.
        // This allows the cursor to "see through" the item and detect which container we are over.
-       commands.entity(entity).insert(PickingBehavior::IGNORE);
+       for (cell, parent) in q_cells.iter() {
+           if parent.get() == entity {
+               commands.entity(cell).insert(PickingBehavior::IGNORE);
+           }
+       }
+
+       // 4. A bag's contents ride on top of it
+       for item in grid_state.bag_contents(entity) {
+           commands.entity(item).insert(ZIndex(101));
+       }
    }
 }
 
 /// Drag process (visual update)
 fn on_drag(
    trigger: Trigger<Pointer<Drag>>,
-   mut q_node: Query<&mut Node>,
+   mut q_node: Query<&mut Node, With<InventoryItem>>,
 ) {
    // We update only visual position (Style).
    // Validation logic runs separately in update_drag_visuals.
@@ -307,74 +541,73 @@ fn on_drag(
    }
 }
 
+type DroppedItemQuery<'w, 's> = Query<
+   'w, 's,
+   (&'static Node, &'static mut GridPosition, &'static mut ItemRotation, &'static mut ItemFlip, &'static InventoryItem, Option<&'static Bag>),
+>;
+
 /// End drag (LMB released)
 fn on_drag_end(
    trigger: Trigger<Pointer<DragEnd>>,
    mut commands: Commands,
-   // Use ParamSet to resolve borrow conflicts
-   mut queries: ParamSet<(
-       Query<(Entity, &Node, &mut GridPosition, &mut ItemRotation, &InventoryItem, Option<&Bag>, Has<InStorage>)>, // Mutable
-       (
-           Query<(Entity, &GridPosition, &ItemRotation, &Bag)>, // Bags Read-Only
-           Query<(Entity, &GridPosition, &ItemRotation, &InventoryItem), Without<Bag>> // Items Read-Only
-       )
-   )>,
-   mut grid_state: ResMut<InventoryGridState>,
+   mut q_items: DroppedItemQuery,
+   mut q_cells: Query<(Entity, &Parent, &mut BackgroundColor), With<ItemCell>>,
+   grid_state: Res<InventoryGridState>,
    mut interaction: ResMut<InteractionState>,
-   mut ev_changed: EventWriter<InventoryChangedEvent>,
 ) {
    let entity = trigger.entity();
 
-   // Restore interactivity to item
-   commands.entity(entity).insert(PickingBehavior::default());
-   commands.entity(entity).insert(ZIndex(10)); // Reset Z-index
-
-   let mut placement_success = false;
-
-   {
-       let mut q_mutable = queries.p0();
-       if let Ok((_, node, mut grid_pos, mut rot, item_def, is_bag, _)) = q_mutable.get_mut(entity) {
-           // Determine current Node coordinates
-           let current_left = if let Val::Px(l) = node.left { l } else { 0.0 };
-           let current_top = if let Val::Px(t) = node.top { t } else { 0.0 };
-
-           // Grid Snapping
-           // Round to nearest grid integer index
-           let grid_x = (current_left / GRID_STEP).round() as i32;
-           let grid_y = (current_top / GRID_STEP).round() as i32;
-           let target_pos = IVec2::new(grid_x, grid_y);
+   // Items on a dragged bag move with it (collected before anything changes)
+   let mut carried = None;
+   if let Ok((node, mut grid_pos, mut rot, mut flip, item_def, is_bag)) = q_items.get_mut(entity) {
+       // Restore interactivity and colour to the item's cells
+       for (cell, parent, mut bg) in q_cells.iter_mut() {
+           if parent.get() == entity {
+               commands.entity(cell).insert(PickingBehavior::default());
+               bg.0 = item_color(is_bag.is_some());
+           }
+       }
 
-           // Validation Logic
-           // In real game need check if we are over GridContainer
-           // For simplicity: if coordinates valid for placement, we are over grid.
+       // Reset Z-index: bags stay below items, including the contents raised on drag start
+       let contents = if is_bag.is_some() { grid_state.bag_contents(entity) } else { HashSet::new() };
+       commands.entity(entity).insert(ZIndex(if is_bag.is_some() { 1 } else { 10 }));
+       for &item in &contents {
+           commands.entity(item).insert(ZIndex(10));
+       }
 
-           let valid = if is_bag.is_some() {
-               grid_state.can_place_bag(&item_def.base_shape, target_pos, rot.0, Some(entity))
+       // Grid Snapping
+       let shape = mirror_shape(&item_def.base_shape, flip.0);
+       let target_pos = snapped_grid_pos(node, &shape, rot.0);
+
+       // Validation Logic
+       // In real game need check if we are over GridContainer
+       // For simplicity: if coordinates valid for placement, we are over grid.
+       let from = (interaction.original_grid_pos, interaction.original_rotation, interaction.original_flipped);
+       let to = (target_pos, rot.0, flip.0);
+       if grid_state.can_drop(entity, &item_def.base_shape, is_bag.is_some(), from, to) {
+           // COMMIT: Apply changes
+           grid_pos.0 = target_pos;
+           if drops_into_storage(&shape, (target_pos, rot.0)) {
+               commands.entity(entity).insert(InStorage);
            } else {
-               grid_state.can_place_item(&item_def.base_shape, target_pos, rot.0, Some(entity))
-           };
-
-           if valid {
-               // COMMIT: Apply changes
-               grid_pos.0 = target_pos;
                commands.entity(entity).remove::<InStorage>();
-               placement_success = true;
-               ev_changed.send(InventoryChangedEvent);
-           } else {
-               // REVERT: Rollback to original state
-               grid_pos.0 = interaction.original_grid_pos;
-               rot.0 = interaction.original_rotation;
-               if interaction.was_in_storage {
-                   commands.entity(entity).insert(InStorage);
-               }
+               carried = Some((contents, from, to));
            }
+           // Queued after the InStorage change so the rebuild sees it
+           commands.run_system_cached(rebuild_inventory_grid);
+       } else {
+           // REVERT: Rollback to original state (the InStorage marker is untouched while dragging)
+           grid_pos.0 = interaction.original_grid_pos;
+           rot.0 = interaction.original_rotation;
+           flip.0 = interaction.original_flipped;
        }
    }
 
-   // If placement successful, need to rebuild grid state
-   if placement_success {
-       let (bags, items) = queries.p1();
-       grid_state.rebuild(&bags, &items);
+   if let Some((contents, from, to)) = carried {
+       for item in contents {
+           let Ok((_, mut grid_pos, mut rot, mut flip, _, _)) = q_items.get_mut(item) else { continue; };
+           (grid_pos.0, rot.0, flip.0) = carried_item(grid_pos.0, rot.0, flip.0, from, to);
+       }
    }
 
    // Clear interaction state
@@ -387,11 +620,26 @@ fn on_drag_end(
 
 /// Syncs visual Node position with logical GridPosition.
 /// Ensures "snapping" after drop and drift correction.
+/// The contents of a dragged bag follow the mouse along with it.
 fn update_item_transforms(
-   mut q_items: Query<(Entity, &mut Node, &GridPosition), With<InventoryItem>>,
+   mut q_items: Query<(Entity, &mut Node, &GridPosition, &ItemRotation, &ItemFlip, &InventoryItem)>,
    interaction: Res<InteractionState>,
+   grid_state: Res<InventoryGridState>,
 ) {
-   for (e, mut node, pos) in q_items.iter_mut() {
+   // Pixel offset of the dragged bag's origin cell from where it was picked up
+   let mut drag_offset = Vec2::ZERO;
+   let mut carried = HashSet::new();
+   if let Some(dragged) = interaction.dragged_entity {
+       if let Ok((_, node, _, rot, flip, item)) = q_items.get(dragged) {
+           if let (Val::Px(left), Val::Px(top)) = (node.left, node.top) {
+               let origin = Vec2::new(left, top) - item_node_origin(IVec2::ZERO, &mirror_shape(&item.base_shape, flip.0), rot.0);
+               drag_offset = origin - interaction.original_grid_pos.as_vec2() * GRID_STEP;
+               carried = grid_state.bag_contents(dragged);
+           }
+       }
+   }
+
+   for (e, mut node, pos, rot, flip, item) in q_items.iter_mut() {
        // Skip the item currently being dragged, as its position is controlled by the mouse
        if let Some(dragged) = interaction.dragged_entity {
            if e == dragged {
@@ -399,8 +647,9 @@ fn update_item_transforms(
            }
        }
 
-       let target_x = pos.0.x as f32 * GRID_STEP;
-       let target_y = pos.0.y as f32 * GRID_STEP;
+       let offset = if carried.contains(&e) { drag_offset } else { Vec2::ZERO };
+       let target = item_node_origin(pos.0, &mirror_shape(&item.base_shape, flip.0), rot.0) + offset;
+       let (target_x, target_y) = (target.x, target.y);
 
        // Update only if position differs to avoid unnecessary layout recalc
        // Use small epsilon for float comparison
@@ -414,49 +663,130 @@ fn update_item_transforms(
    }
 }
 
-/// Runs every frame during Drag: provides tint (Green/Red) and rotation
+/// Runs every frame during Drag: provides tint (Green/Red), rotation and mirroring
 fn update_drag_visuals(
    interaction: Res<InteractionState>,
-   mut q_dragged: Query<(&mut Node, &mut BackgroundColor, &mut ItemRotation, &InventoryItem, Option<&Bag>)>,
+   mut q_dragged: Query<(&mut Node, &mut ItemRotation, &mut ItemFlip, &InventoryItem, Option<&Bag>)>,
+   mut q_cells: Query<(&Parent, &mut BackgroundColor), With<ItemCell>>,
    grid_state: Res<InventoryGridState>,
    input: Res<ButtonInput<KeyCode>>,
 ) {
    let Some(entity) = interaction.dragged_entity else { return; };
 
-   if let Ok((mut node, mut bg, mut rot, item_def, is_bag)) = q_dragged.get_mut(entity) {
-
-       // 1. Handle rotation (R)
-       if input.just_pressed(KeyCode::KeyR) {
-           rot.0 = (rot.0 + 1) % 4;
-           // Visually swap width/height for preview
-           // Note: works for rectangles. Complex shapes need texture/mesh rotation.
-           let temp = node.width;
-           node.width = node.height;
-           node.height = temp;
+   if let Ok((mut node, mut rot, mut flip, item_def, is_bag)) = q_dragged.get_mut(entity) {
+
+       // 1. Handle rotation (R) and mirroring (F)
+       let rotate = input.just_pressed(KeyCode::KeyR);
+       let mirror = input.just_pressed(KeyCode::KeyF);
+       if rotate || mirror {
+           // Turn or mirror around the origin cell: shift the box so that cell stays put.
+           // Cells and box size follow in layout_item_cells.
+           let before = item_node_origin(IVec2::ZERO, &mirror_shape(&item_def.base_shape, flip.0), rot.0);
+           if rotate { rot.0 = (rot.0 + 1) % 4; }
+           if mirror { flip.0 = !flip.0; }
+           let shift = item_node_origin(IVec2::ZERO, &mirror_shape(&item_def.base_shape, flip.0), rot.0) - before;
+           if let Val::Px(x) = node.left { node.left = Val::Px(x + shift.x); }
+           if let Val::Px(y) = node.top { node.top = Val::Px(y + shift.y); }
        }
 
        // 2. Calculate "Ghost" position
-       let current_left = if let Val::Px(v) = node.left { v } else { 0.0 };
-       let current_top = if let Val::Px(v) = node.top { v } else { 0.0 };
-
-       let grid_x = (current_left / GRID_STEP).round() as i32;
-       let grid_y = (current_top / GRID_STEP).round() as i32;
-       let target_pos = IVec2::new(grid_x, grid_y);
+       let target_pos = snapped_grid_pos(&node, &mirror_shape(&item_def.base_shape, flip.0), rot.0);
 
        // 3. Real-time Validation
-       let is_valid = if is_bag.is_some() {
-           grid_state.can_place_bag(&item_def.base_shape, target_pos, rot.0, Some(entity))
+       let from = (interaction.original_grid_pos, interaction.original_rotation, interaction.original_flipped);
+       let is_valid = grid_state.can_drop(entity, &item_def.base_shape, is_bag.is_some(), from, (target_pos, rot.0, flip.0));
+
+       // 4. Apply Tint
+       let tint = if is_valid {
+           Color::srgba(0.5, 1.0, 0.5, 0.8) // Translucent Green
        } else {
-           grid_state.can_place_item(&item_def.base_shape, target_pos, rot.0, Some(entity))
+           Color::srgba(1.0, 0.5, 0.5, 0.8) // Translucent Red
        };
+       for (parent, mut bg) in q_cells.iter_mut() {
+           if parent.get() == entity {
+               bg.0 = tint;
+           }
+       }
+   }
+}
 
-       // 4. Apply Tint
-       if is_valid {
-           *bg = BackgroundColor(Color::srgba(0.5, 1.0, 0.5, 0.8)); // Translucent Green
+type ItemLayoutChangedQuery<'w, 's> = Query<
+   'w, 's,
+   (Entity, &'static InventoryItem, &'static ItemRotation, &'static ItemFlip, &'static mut Node, Option<&'static Children>, Has<Bag>),
+   Or<(Changed<ItemRotation>, Changed<ItemFlip>, Changed<InventoryItem>)>,
+>;
+
+/// Sizes each item's bounding box and places its cells for the current shape, rotation and flip.
+/// A reloaded shape with a different cell count gets fresh cells.
+fn layout_item_cells(
+   mut commands: Commands,
+   item_db: Res<ItemDatabase>,
+   mut q_items: ItemLayoutChangedQuery,
+   mut q_cells: Query<(&ItemCell, &mut Node), Without<InventoryItem>>,
+) {
+   for (entity, item, rot, flip, mut node, children, is_bag) in q_items.iter_mut() {
+       let extent = shape_bounds(&mirror_shape(&item.base_shape, flip.0), rot.0).size() + IVec2::ONE;
+       node.width = Val::Px(extent.x as f32 * GRID_STEP - CELL_GAP);
+       node.height = Val::Px(extent.y as f32 * GRID_STEP - CELL_GAP);
+
+       let cells: Vec<Entity> = children.into_iter().flatten().copied().filter(|c| q_cells.contains(*c)).collect();
+       let matches_shape = cells.len() == item.base_shape.len()
+           && cells.iter().all(|c| q_cells.get(*c).is_ok_and(|(cell, _)| item.base_shape.contains(&cell.0)));
+       if matches_shape {
+           for cell in cells {
+               let Ok((offset, mut cell_node)) = q_cells.get_mut(cell) else { continue; };
+               let local = cell_position(offset.0, &item.base_shape, rot.0, flip.0);
+               cell_node.left = Val::Px(local.x);
+               cell_node.top = Val::Px(local.y);
+           }
        } else {
-           *bg = BackgroundColor(Color::srgba(1.0, 0.5, 0.5, 0.8)); // Translucent Red
+           for cell in cells {
+               commands.entity(cell).despawn_recursive();
+           }
+           let name = item_db.items.get(&item.item_id).map_or(item.item_id.as_str(), |def| def.name.as_str());
+           spawn_item_cells(&mut commands, entity, &item.base_shape, rot.0, flip.0, name, item_color(is_bag));
+       }
+   }
+}
+
+type ReloadedItemQuery<'w, 's> = Query<
+   'w, 's,
+   (&'static mut InventoryItem, Option<&'static mut Bag>, &'static Parent),
+>;
+
+/// Applies reloaded item definitions to already spawned grid items
+/// (shape, provided slots) and rebuilds the grid.
+fn refresh_items_on_reload(
+   mut ev_reloaded: EventReader<ItemDatabaseReloaded>,
+   item_db: Res<ItemDatabase>,
+   mut queries: ParamSet<(ReloadedItemQuery, (BagLayoutQuery, ItemLayoutQuery))>,
+   q_container: Query<Entity, With<InventoryGridContainer>>,
+   mut grid_state: ResMut<InventoryGridState>,
+   mut ev_changed: EventWriter<InventoryChangedEvent>,
+) {
+   if ev_reloaded.read().count() == 0 { return; }
+   let Ok(container) = q_container.get_single() else { return; };
+
+   for (mut item, bag, parent) in queries.p0().iter_mut() {
+       // Only grid items; shop previews have their own sizing
+       if parent.get() != container { continue; }
+       let Some(def) = item_db.items.get(&item.item_id) else {
+           warn!("Item '{}' no longer exists in the database", item.item_id);
+           continue;
+       };
+
+       item.base_shape = def.shape.clone();
+       item.width = def.width;
+       item.height = def.height;
+       if let Some(mut bag) = bag {
+           bag.provided_slots = def.shape.clone();
        }
+       // Box and cells are redrawn by layout_item_cells
    }
+
+   let (bags, items) = queries.p1();
+   grid_state.rebuild(&bags, &items);
+   ev_changed.send(InventoryChangedEvent);
 }
 
 // ============================================================================
@@ -480,7 +810,7 @@ fn setup_inventory_ui(mut commands: Commands) {
    )).with_children(|parent| {
 
        parent.spawn((
-           Text::new("Inventory Mode (Drag to Move, R to Rotate)"),
+           Text::new("Inventory Mode (Drag to Move, R to Rotate, F to Flip)"),
            TextFont { font_size: 20.0,..default() },
            TextColor(Color::WHITE),
            Node { margin: UiRect::bottom(Val::Px(20.0)),..default() }
@@ -498,10 +828,156 @@ fn setup_inventory_ui(mut commands: Commands) {
            BorderColor(Color::WHITE),
            BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
            InventoryGridContainer,
-       ));
+       )).with_children(|grid| {
+           // Storage strip (Limbo): items parked here don't count for combat
+           grid.spawn((
+               Node {
+                   position_type: PositionType::Absolute,
+                   left: Val::Px(STORAGE_COLUMN as f32 * GRID_STEP),
+                   top: Val::Px(0.0),
+                   width: Val::Px(STORAGE_WIDTH as f32 * GRID_STEP),
+                   height: Val::Px(STORAGE_HEIGHT as f32 * GRID_STEP),
+                   border: UiRect::left(Val::Px(2.0)),
+                  ..default()
+               },
+               BorderColor(Color::srgb(0.5, 0.5, 0.5)),
+               BackgroundColor(Color::srgb(0.2, 0.17, 0.12)),
+               ZIndex(0),
+               PickingBehavior::IGNORE,
+               InventoryStorageStrip,
+           )).with_child((
+               Text::new("Storage"),
+               TextFont { font_size: 12.0,..default() },
+               TextColor(Color::srgb(0.7, 0.7, 0.7)),
+               PickingBehavior::IGNORE,
+           ));
+       });
+   });
+}
+
+/// Respawns the persistent inventory into the freshly built grid container.
+fn restore_inventory_grid(
+   mut commands: Commands,
+   persistent_inventory: Res<PersistentInventory>,
+   item_db: Res<ItemDatabase>,
+   q_container: Query<Entity, With<InventoryGridContainer>>,
+   mut grid_state: ResMut<InventoryGridState>,
+) {
+   let Ok(container) = q_container.get_single() else { return; };
+   spawn_saved_items(&mut commands, container, &persistent_inventory.items, &item_db, &mut grid_state);
+}
+
+/// Rebuilds the grid maps from the spawned items, so placement checks see them.
+/// Also queued as a one-off system after drops and purchases.
+pub fn rebuild_inventory_grid(
+   bags: BagLayoutQuery,
+   items: ItemLayoutQuery,
+   mut grid_state: ResMut<InventoryGridState>,
+   mut ev_changed: EventWriter<InventoryChangedEvent>,
+) {
+   grid_state.rebuild(&bags, &items);
+   ev_changed.send(InventoryChangedEvent);
+}
+
+/// Parks items found during the day (and night loot) in the storage strip.
+/// Whatever doesn't fit waits for the next evening.
+fn unpack_pending_items(
+   mut commands: Commands,
+   mut pending_items: ResMut<PendingItems>,
+   item_db: Res<ItemDatabase>,
+   q_container: Query<Entity, With<InventoryGridContainer>>,
+   mut grid_state: ResMut<InventoryGridState>,
+) {
+   let Ok(container) = q_container.get_single() else { return; };
+   pending_items.0.retain(|item_id| {
+       let Some(def) = item_db.items.get(item_id) else {
+           warn!("Pending item '{}' is not a known item", item_id);
+           return false;
+       };
+       store_new_item(&mut commands, container, def, &mut grid_state).is_none()
    });
 }
 
+/// Spawns a new item straight into the first free storage spot.
+/// The cells are reserved right away so several items can be stored in one go.
+pub fn store_new_item(
+   commands: &mut Commands,
+   container: Entity,
+   def: &ItemDefinition,
+   grid_state: &mut InventoryGridState,
+) -> Option<Entity> {
+   let (pos, rot) = grid_state.find_storage_spot(&def.shape)?;
+   let entity = spawn_item_entity(commands, container, def, pos, rot, grid_state);
+   commands.entity(entity).insert(InStorage);
+   for offset in rotate_shape(&def.shape, rot) {
+       grid_state.storage.insert(pos + offset, entity);
+   }
+   Some(entity)
+}
+
+/// Writes the grid back into the persistent inventory before it is despawned.
+/// Stored items are kept or dropped according to the `StorageRule`.
+fn save_inventory_grid(
+   q_items: SnapshotQuery,
+   q_container: Query<Entity, With<InventoryGridContainer>>,
+   storage_rule: Res<StorageRule>,
+   mut persistent_inventory: ResMut<PersistentInventory>,
+) {
+   let Ok(container) = q_container.get_single() else { return; };
+   let mut items = snapshot_inventory(&q_items, container);
+   if *storage_rule == StorageRule::Discard {
+       items.retain(|saved| !saved.in_storage);
+   }
+   persistent_inventory.items = items;
+}
+
+pub type SnapshotQuery<'w, 's> = Query<
+   'w, 's,
+   (&'static InventoryItem, &'static GridPosition, &'static ItemRotation, &'static ItemFlip, &'static Parent, Has<InStorage>),
+>;
+
+/// Saved entries for everything on the grid or in storage. Shop previews are skipped.
+pub fn snapshot_inventory(q_items: &SnapshotQuery, container: Entity) -> Vec<SavedItem> {
+   q_items.iter()
+       .filter(|(_, _, _, _, parent, in_storage)| *in_storage || parent.get() == container)
+       .map(|(item, pos, rot, flip, _, in_storage)| SavedItem {
+           item_id: item.item_id.clone(),
+           grid_x: pos.0.x,
+           grid_y: pos.0.y,
+           rotation: rot.0,
+           flipped: flip.0,
+           in_storage,
+       })
+       .collect()
+}
+
+/// Spawns saved items into the grid container, bags first so their slots exist before the items.
+pub fn spawn_saved_items(
+   commands: &mut Commands,
+   container: Entity,
+   items: &[SavedItem],
+   item_db: &ItemDatabase,
+   grid_state: &mut InventoryGridState,
+) {
+   let (bags, others): (Vec<&SavedItem>, Vec<&SavedItem>) = items.iter()
+       .partition(|saved| item_db.items.get(&saved.item_id).is_some_and(|def| matches!(def.item_type, ItemType::Bag { .. })));
+
+   for saved in bags.into_iter().chain(others) {
+       let Some(def) = item_db.items.get(&saved.item_id) else {
+           warn!("Saved item '{}' no longer exists in the database", saved.item_id);
+           continue;
+       };
+       let entity = spawn_item_entity(commands, container, def, IVec2::new(saved.grid_x, saved.grid_y), saved.rotation, grid_state);
+       if saved.flipped {
+           // Box and cells are mirrored by layout_item_cells
+           commands.entity(entity).insert(ItemFlip(true));
+       }
+       if saved.in_storage {
+           commands.entity(entity).insert(InStorage);
+       }
+   }
+}
+
 fn cleanup_inventory(
    mut commands: Commands,
    q: Query<Entity, With<InventoryUiRoot>>,
@@ -510,6 +986,7 @@ fn cleanup_inventory(
 }
 
 /// Helper for spawning items. Used by other plugins (Shop, LoadGame).
+/// The item node is a transparent box around its rotated shape; the shape is drawn as `ItemCell` children.
 pub fn spawn_item_entity(
    commands: &mut Commands,
    parent: Entity,
@@ -517,33 +994,24 @@ pub fn spawn_item_entity(
    pos: IVec2,
    rot: u8,
    _grid_state: &mut InventoryGridState,
-) {
-   // Determine pixels size with rotation
-   let (w, h) = if rot % 2 == 0 { (def.width, def.height) } else { (def.height, def.width) };
-
-   let width_px = w as f32 * GRID_STEP - CELL_GAP;
-   let height_px = h as f32 * GRID_STEP - CELL_GAP;
-   let x_px = pos.x as f32 * GRID_STEP;
-   let y_px = pos.y as f32 * GRID_STEP;
+) -> Entity {
+   let origin = item_node_origin(pos, &def.shape, rot);
+   let extent = shape_bounds(&def.shape, rot).size() + IVec2::ONE;
 
    let is_bag = matches!(def.item_type, ItemType::Bag {..});
    // Bags lower (Z=1), items higher (Z=10)
-   let color = if is_bag { Color::srgb(0.6, 0.4, 0.2) } else { Color::srgb(0.3, 0.3, 0.8) };
    let z = if is_bag { 1 } else { 10 };
 
    let id = commands.spawn((
        Node {
            position_type: PositionType::Absolute,
-           left: Val::Px(x_px),
-           top: Val::Px(y_px),
-           width: Val::Px(width_px),
-           height: Val::Px(height_px),
-           border: UiRect::all(Val::Px(1.0)),
+           left: Val::Px(origin.x),
+           top: Val::Px(origin.y),
+           width: Val::Px(extent.x as f32 * GRID_STEP - CELL_GAP),
+           height: Val::Px(extent.y as f32 * GRID_STEP - CELL_GAP),
            // Important: padding and margins can mess up calculations, use absolute positioning
           ..default()
        },
-       BackgroundColor(color),
-       BorderColor(Color::BLACK),
        InventoryItem {
            item_id: def.id.clone(),
            base_shape: def.shape.clone(),
@@ -552,20 +1020,254 @@ pub fn spawn_item_entity(
        },
        GridPosition(pos),
        ItemRotation(rot),
+       ItemFlip(false),
        ZIndex(z),
-       PickingBehavior::default(), // Enable Picking explicitly
-   )).with_children(|p| {
-       p.spawn((
-           Text::new(&def.name),
-           TextFont { font_size: 10.0,..default() },
-           TextColor(Color::WHITE),
-           PickingBehavior::IGNORE, // Text should not capture clicks
-       ));
-   }).id();
+       PickingBehavior::IGNORE, // Only the cells are hit, not the gaps of the bounding box
+   )).id();
+   spawn_item_cells(commands, id, &def.shape, rot, false, &def.name, item_color(is_bag));
 
    if is_bag {
        commands.entity(id).insert(Bag { provided_slots: def.shape.clone() });
    }
 
    commands.entity(parent).add_child(id);
+   id
+}
+
+/// Base colour of an item's cells.
+fn item_color(is_bag: bool) -> Color {
+   if is_bag { Color::srgb(0.6, 0.4, 0.2) } else { Color::srgb(0.3, 0.3, 0.8) }
+}
+
+/// Pixel position of a shape cell (an offset in the unrotated, unmirrored shape) inside its item's node.
+fn cell_position(offset: IVec2, shape: &[IVec2], rot: u8, flipped: bool) -> Vec2 {
+   (orient_shape(&[offset], rot, flipped)[0] - shape_bounds(&mirror_shape(shape, flipped), rot).min).as_vec2() * GRID_STEP
+}
+
+/// Spawns one pickable cell per shape offset under `item`. The name goes on the first cell.
+fn spawn_item_cells(commands: &mut Commands, item: Entity, shape: &[IVec2], rot: u8, flipped: bool, name: &str, color: Color) {
+   commands.entity(item).with_children(|p| {
+       for (index, &offset) in shape.iter().enumerate() {
+           let local = cell_position(offset, shape, rot, flipped);
+           let mut cell = p.spawn((
+               Node {
+                   position_type: PositionType::Absolute,
+                   left: Val::Px(local.x),
+                   top: Val::Px(local.y),
+                   width: Val::Px(CELL_SIZE - CELL_GAP),
+                   height: Val::Px(CELL_SIZE - CELL_GAP),
+                   border: UiRect::all(Val::Px(1.0)),
+                  ..default()
+               },
+               BackgroundColor(color),
+               BorderColor(Color::BLACK),
+               ItemCell(offset),
+               PickingBehavior::default(), // Enable Picking explicitly
+           ));
+           if index == 0 {
+               cell.with_child((
+                   Text::new(name),
+                   TextFont { font_size: 10.0,..default() },
+                   TextColor(Color::WHITE),
+                   PickingBehavior::IGNORE, // Text should not capture clicks
+               ));
+           }
+       }
+   });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_polyomino_cells_turn_around_the_origin_cell() {
+        let t_shape = vec![IVec2::new(0, 0), IVec2::new(1, 0), IVec2::new(2, 0), IVec2::new(1, 1)];
+        let pos = IVec2::new(3, 3);
+
+        // Whatever the rotation, the origin cell is drawn at the item's grid position and the node snaps back to it
+        for rot in 0..4 {
+            let origin = item_node_origin(pos, &t_shape, rot);
+            assert_eq!(origin + cell_position(IVec2::ZERO, &t_shape, rot, false), pos.as_vec2() * GRID_STEP);
+            let node = Node { left: Val::Px(origin.x), top: Val::Px(origin.y), ..default() };
+            assert_eq!(snapped_grid_pos(&node, &t_shape, rot), pos);
+        }
+
+        let mut world = World::new();
+        world.init_resource::<ItemDatabase>();
+        let container = world.spawn(Node::default()).id();
+        let def = ItemDefinition { id: "t".to_string(), name: "T".to_string(), width: 3, height: 2, shape: t_shape, ..default() };
+        let item = spawn_item_entity(&mut world.commands(), container, &def, pos, 0, &mut InventoryGridState::default());
+        world.flush();
+
+        let mut layout = Schedule::default();
+        layout.add_systems(layout_item_cells);
+        world.get_mut::<ItemRotation>(item).unwrap().0 = 1;
+        layout.run(&mut world);
+
+        // Turned once the T stands 2 wide and 3 tall, its stem pointing left
+        let node = world.get::<Node>(item).unwrap();
+        assert_eq!((node.width, node.height), (Val::Px(2.0 * GRID_STEP - CELL_GAP), Val::Px(3.0 * GRID_STEP - CELL_GAP)));
+        let mut cells: Vec<(IVec2, Val, Val)> = world.query::<(&ItemCell, &Node)>().iter(&world)
+            .map(|(cell, node)| (cell.0, node.left, node.top))
+            .collect();
+        cells.sort_by_key(|(offset, _, _)| (offset.x, offset.y));
+        assert_eq!(cells, vec![
+            (IVec2::new(0, 0), Val::Px(GRID_STEP), Val::Px(0.0)),
+            (IVec2::new(1, 0), Val::Px(GRID_STEP), Val::Px(GRID_STEP)),
+            (IVec2::new(1, 1), Val::Px(0.0), Val::Px(GRID_STEP)),
+            (IVec2::new(2, 0), Val::Px(GRID_STEP), Val::Px(2.0 * GRID_STEP)),
+        ]);
+    }
+
+    #[test]
+    fn test_bag_moves_carry_their_contents() {
+        let mut world = World::new();
+        let (bag, side_bag, sword, strap) = (world.spawn_empty().id(), world.spawn_empty().id(), world.spawn_empty().id(), world.spawn_empty().id());
+        let bag_shape = [IVec2::new(0, 0), IVec2::new(1, 0), IVec2::new(0, 1), IVec2::new(1, 1)];
+
+        // 2x2 bag at the origin with a vertical sword in its left column
+        let mut grid = InventoryGridState::default();
+        for cell in bag_shape {
+            grid.slots.insert(cell, bag);
+        }
+        grid.occupancy.insert(IVec2::new(0, 0), sword);
+        grid.occupancy.insert(IVec2::new(0, 1), sword);
+        assert_eq!(grid.bag_contents(bag), HashSet::from_iter([sword]));
+
+        let origin = (IVec2::ZERO, 0, false);
+        assert!(grid.can_move_bag(&bag_shape, bag, origin, (IVec2::new(5, 5), 0, false)));
+        assert_eq!(carried_item(IVec2::ZERO, 0, false, origin, (IVec2::new(5, 5), 0, false)), (IVec2::new(5, 5), 0, false));
+
+        // Turning the bag in place lays the sword across its top row
+        let turned = (IVec2::new(1, 0), 1, false);
+        assert!(grid.can_move_bag(&bag_shape, bag, origin, turned));
+        let (pos, rot, _) = carried_item(IVec2::ZERO, 0, false, origin, turned);
+        let cells: Vec<IVec2> = rotate_shape(&[IVec2::ZERO, IVec2::Y], rot).into_iter().map(|o| pos + o).collect();
+        assert_eq!(cells, vec![IVec2::new(1, 0), IVec2::new(0, 0)]);
+
+        // A strap lying across both bags pins them in place
+        grid.slots.insert(IVec2::new(2, 1), side_bag);
+        grid.occupancy.insert(IVec2::new(1, 1), strap);
+        grid.occupancy.insert(IVec2::new(2, 1), strap);
+        assert_eq!(grid.bag_contents(bag), HashSet::from_iter([sword]));
+        assert!(!grid.can_move_bag(&bag_shape, bag, origin, (IVec2::new(5, 5), 0, false)));
+        assert!(grid.can_move_bag(&bag_shape, bag, origin, origin));
+        // Nor may the bag be dropped onto another bag
+        grid.occupancy.remove(&IVec2::new(1, 1));
+        grid.occupancy.remove(&IVec2::new(2, 1));
+        assert!(!grid.can_move_bag(&bag_shape, bag, origin, (IVec2::new(1, 0), 0, false)));
+    }
+
+    #[test]
+    fn test_flipped_bags_mirror_their_contents() {
+        let l_shape = [IVec2::new(0, 0), IVec2::new(1, 0), IVec2::new(0, 1)];
+        assert_eq!(orient_shape(&l_shape, 0, true), vec![IVec2::new(0, 0), IVec2::new(-1, 0), IVec2::new(0, 1)]);
+        // Mirroring then turning twice is the same as mirroring top to bottom
+        assert_eq!(orient_shape(&l_shape, 2, true), vec![IVec2::new(0, 0), IVec2::new(1, 0), IVec2::new(0, -1)]);
+
+        // Whatever the bag does, a carried item's cells follow the bag's cells
+        let sword = [IVec2::ZERO, IVec2::X];
+        let from = (IVec2::new(2, 2), 1, false);
+        let (item_pos, item_rot, item_flip) = (IVec2::new(2, 3), 3, true);
+        for to in [(IVec2::new(4, 4), 0, true), (IVec2::new(4, 4), 3, true), (IVec2::new(4, 4), 2, false)] {
+            let (pos, rot, flipped) = carried_item(item_pos, item_rot, item_flip, from, to);
+            let cells: Vec<IVec2> = orient_shape(&sword, rot, flipped).into_iter().map(|o| pos + o).collect();
+            let expected: Vec<IVec2> = orient_shape(&sword, item_rot, item_flip).into_iter()
+                .map(|o| carried_cell(item_pos + o, from, to))
+                .collect();
+            assert_eq!(cells, expected);
+        }
+
+        // The origin cell stays at the grid position when the drawn item is mirrored
+        let t_shape = [IVec2::new(0, 0), IVec2::new(1, 0), IVec2::new(2, 0), IVec2::new(1, 1)];
+        for rot in 0..4 {
+            let origin = item_node_origin(IVec2::new(3, 3), &mirror_shape(&t_shape, true), rot);
+            assert_eq!(origin + cell_position(IVec2::ZERO, &t_shape, rot, true), IVec2::new(3, 3).as_vec2() * GRID_STEP);
+        }
+    }
+
+    #[test]
+    fn test_grid_round_trips_through_persistent_inventory() {
+        let mut world = World::new();
+        world.insert_resource(ItemDatabase::shipped());
+        world.init_resource::<InventoryGridState>();
+        world.init_resource::<Events<InventoryChangedEvent>>();
+        world.init_resource::<StorageRule>();
+        world.insert_resource(PersistentInventory {
+            items: vec![
+                SavedItem::new("steel_sword", 3, 2, 1),
+                SavedItem::new("starter_bag", 2, 2, 0),
+                SavedItem { in_storage: true, ..SavedItem::new("silver_dagger", 10, 0, 0) },
+            ],
+        });
+
+        let mut enter = Schedule::default();
+        enter.add_systems((setup_inventory_ui, restore_inventory_grid, rebuild_inventory_grid).chain());
+        enter.run(&mut world);
+
+        // The rotated sword lies across (2,2) and (3,2) of the bag
+        let grid_state = world.resource::<InventoryGridState>();
+        assert_eq!(grid_state.slots.len(), 9);
+        let sword = grid_state.occupancy[&IVec2::new(2, 2)];
+        assert_eq!(grid_state.occupancy.get(&IVec2::new(3, 2)), Some(&sword));
+        assert_eq!(grid_state.storage.len(), 1);
+        let mut stored = world.query_filtered::<&InventoryItem, With<InStorage>>();
+        assert_eq!(stored.single(&world).item_id, "silver_dagger");
+
+        world.get_mut::<GridPosition>(sword).unwrap().0 = IVec2::new(4, 3);
+        world.get_mut::<ItemFlip>(sword).unwrap().0 = true;
+        let mut exit = Schedule::default();
+        exit.add_systems((save_inventory_grid, cleanup_inventory).chain());
+        exit.run(&mut world);
+
+        let mut items = world.resource::<PersistentInventory>().items.clone();
+        items.sort_by(|a, b| a.item_id.cmp(&b.item_id));
+        let summary: Vec<_> = items.iter().map(|s| (s.item_id.as_str(), s.grid_x, s.grid_y, s.rotation, s.flipped, s.in_storage)).collect();
+        assert_eq!(summary, vec![
+            ("silver_dagger", 10, 0, 0, false, true),
+            ("starter_bag", 2, 2, 0, false, false),
+            ("steel_sword", 4, 3, 1, true, false),
+        ]);
+        assert_eq!(world.query::<&InventoryItem>().iter(&world).count(), 0);
+
+        // Under the discard rule the stored dagger doesn't survive the evening
+        world.insert_resource(StorageRule::Discard);
+        enter.run(&mut world);
+        exit.run(&mut world);
+        let items = &world.resource::<PersistentInventory>().items;
+        assert_eq!(items.len(), 2);
+        assert!(items.iter().all(|saved| !saved.in_storage));
+    }
+
+    #[test]
+    fn test_storage_strip_parks_items() {
+        let mut world = World::new();
+        let (bag, sword, dagger) = (world.spawn_empty().id(), world.spawn_empty().id(), world.spawn_empty().id());
+        let sword_shape = [IVec2::ZERO, IVec2::Y];
+        let mut grid = InventoryGridState::default();
+        grid.slots.insert(IVec2::ZERO, bag);
+        grid.occupancy.insert(IVec2::ZERO, dagger);
+
+        // The first spot is the strip's top-left corner; a parked sword takes the cells below
+        assert_eq!(grid.find_storage_spot(&sword_shape), Some((IVec2::new(STORAGE_COLUMN, 0), 0)));
+        for cell in sword_shape {
+            grid.storage.insert(IVec2::new(STORAGE_COLUMN, 0) + cell, sword);
+        }
+        assert_eq!(grid.find_storage_spot(&[IVec2::ZERO]), Some((IVec2::new(STORAGE_COLUMN + 1, 0), 0)));
+
+        // Dropping across the strip's edge is a storage attempt, and the sword doesn't fit there
+        let from = (IVec2::ZERO, 0, false);
+        assert!(drops_into_storage(&sword_shape, (IVec2::new(STORAGE_COLUMN, STORAGE_HEIGHT - 1), 0)));
+        assert!(!grid.can_drop(sword, &sword_shape, false, from, (IVec2::new(STORAGE_COLUMN, STORAGE_HEIGHT - 1), 0, false)));
+        assert!(grid.can_drop(sword, &sword_shape, false, from, (IVec2::new(STORAGE_COLUMN + 2, 3), 0, false)));
+        // It can move within storage over its own cells
+        assert!(grid.can_drop(sword, &sword_shape, false, from, (IVec2::new(STORAGE_COLUMN, 1), 0, false)));
+
+        // A bag holding the dagger stays out, and no bag may spread into the strip
+        assert!(!grid.can_drop(bag, &[IVec2::ZERO], true, from, (IVec2::new(STORAGE_COLUMN + 2, 0), 0, false)));
+        grid.occupancy.clear();
+        assert!(grid.can_drop(bag, &[IVec2::ZERO], true, from, (IVec2::new(STORAGE_COLUMN + 2, 0), 0, false)));
+        assert!(!grid.can_place_bag(&[IVec2::ZERO, IVec2::X], IVec2::new(STORAGE_COLUMN - 1, 0), 0, Some(bag)));
+    }
 }
@@ -1,14 +1,86 @@
-use crate::plugins::items::{ItemDatabase, StatType, SynergyEffect};
+use crate::plugins::combat::{ActionMeter, MaterialType, TargetingStrategy};
+use crate::plugins::items::{BagCoverage, BagType, ConsumableDefinition, ItemDatabase, ItemType, RangedDefinition, StatType, StatusApplication, SynergyDefinition, SynergyEffect};
 use crate::plugins::metagame::{PersistentInventory, SavedItem};
 use bevy::prelude::*;
-use bevy::utils::HashMap;
+use bevy::utils::{HashMap, HashSet};
 
+/// Meter fill per tick every weapon gets before its own speed stat.
+pub const BASE_WEAPON_SPEED: f32 = 10.0;
+
+/// Player-level totals plus a breakdown of every weapon.
 #[derive(Default, Debug, Clone)]
 pub struct CombatStats {
+    pub attack: f32,
+    pub defense: f32,
+    /// Speed from everything but weapons (e.g. heavy armor), already added to every weapon's speed
+    pub speed: f32,
+    pub health: f32,
+    pub weapons: Vec<WeaponStats>,
+    pub consumables: Vec<ConsumableStats>,
+    pub ammo: Vec<AmmoStats>,
+}
+
+/// Stats of a single item after synergies.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct ItemStats {
     pub attack: f32,
     pub defense: f32,
     pub speed: f32,
     pub health: f32,
+    /// Statuses inflicted on hit, the item's own followed by ones granted by synergies
+    pub on_hit: Vec<StatusApplication>,
+    /// Bonus to consumable effects (0.5 = +50%)
+    pub potency: f32,
+}
+
+/// One weapon's contribution, so each weapon can attack on its own timer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeaponStats {
+    pub item_id: String,
+    /// Index into `PersistentInventory::items`
+    pub inventory_index: usize,
+    pub damage: f32,
+    /// Weapon's own speed stat plus the gear speed (bonus on top of `BASE_WEAPON_SPEED`)
+    pub speed: f32,
+    pub material: MaterialType,
+    pub targeting: TargetingStrategy,
+    pub on_hit: Vec<StatusApplication>,
+    pub ranged: Option<RangedDefinition>,
+    /// Inventory indices of the ammo a ranged weapon can draw from, in draw order
+    pub ammo: Vec<usize>,
+}
+
+/// A consumable the player brings into the fight.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConsumableStats {
+    pub item_id: String,
+    /// Index into `PersistentInventory::items`, so spent items can be removed afterwards
+    pub inventory_index: usize,
+    pub definition: ConsumableDefinition,
+    /// Multiplier on effect amounts
+    pub potency: f32,
+}
+
+/// An ammo item the player brings into the fight.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AmmoStats {
+    pub item_id: String,
+    /// Index into `PersistentInventory::items`
+    pub inventory_index: usize,
+    pub material: MaterialType,
+    pub rounds: u32,
+}
+
+impl WeaponStats {
+    /// Meter fill per tick.
+    pub fn attack_speed(&self) -> f32 {
+        (BASE_WEAPON_SPEED + self.speed).max(1.0)
+    }
+
+    /// Ticks between attacks with the default `ActionMeter` threshold.
+    pub fn cooldown_ticks(&self) -> f32 {
+        ActionMeter::default().threshold / self.attack_speed()
+    }
 }
 
 pub fn calculate_combat_stats(
@@ -17,97 +89,432 @@ pub fn calculate_combat_stats(
 ) -> CombatStats {
     let mut stats = CombatStats::default();
 
-    // 1. Reconstruct grid to calculate synergies
-    // This is a simplified simulation because PersistentInventory just has a list of items
-    // We need to know where they are relative to each other.
-    // SavedItem has `grid_x` and `grid_y`.
+    // Cells of every item and the bag providing each slot, to find the ammo in a weapon's reach
+    let cells: Vec<Vec<IVec2>> = inventory.items.iter()
+        .map(|item| match db.items.get(&item.item_id) {
+            Some(def) if !item.in_storage => saved_item_cells(item, &def.shape),
+            _ => Vec::new(),
+        })
+        .collect();
+    let mut bag_of: HashMap<IVec2, usize> = HashMap::new();
+    for (index, item) in inventory.items.iter().enumerate() {
+        if db.items.get(&item.item_id).is_some_and(|def| matches!(def.item_type, ItemType::Bag { .. })) {
+            bag_of.extend(cells[index].iter().map(|pos| (*pos, index)));
+        }
+    }
+    let is_ammo = |index: usize| db.items.get(&inventory.items[index].item_id).is_some_and(|def| def.item_type == ItemType::Ammo);
+
+    for (index, item_stats) in calculate_item_stats(inventory, db).into_iter().enumerate() {
+        let item = &inventory.items[index];
+        // Parked items don't come along to the fight
+        if item.in_storage { continue; }
 
-    // Map: GridPos -> (SavedItem)
-    let mut grid_map: HashMap<IVec2, &SavedItem> = HashMap::new();
+        stats.attack += item_stats.attack;
+        stats.defense += item_stats.defense;
+        stats.health += item_stats.health;
 
-    for item in &inventory.items {
         if let Some(def) = db.items.get(&item.item_id) {
-            let shape = crate::plugins::inventory::rotate_shape(&def.shape, item.rotation);
-            for offset in shape {
-                let pos = IVec2::new(item.grid_x, item.grid_y) + offset;
-                grid_map.insert(pos, item);
+            // A weapon's speed only drives its own meter; anything else's is gear speed
+            if def.item_type != ItemType::Weapon {
+                stats.speed += item_stats.speed;
+            }
+
+            if def.item_type == ItemType::Weapon {
+                stats.weapons.push(WeaponStats {
+                    item_id: item.item_id.clone(),
+                    inventory_index: index,
+                    damage: item_stats.attack,
+                    speed: item_stats.speed,
+                    material: def.material.into(),
+                    targeting: def.targeting,
+                    on_hit: item_stats.on_hit,
+                    ranged: def.ranged,
+                    ammo: match def.ranged {
+                        Some(_) => ammo_in_reach(index, &cells, &bag_of, is_ammo),
+                        None => Vec::new(),
+                    },
+                });
+            } else if def.item_type == ItemType::Ammo {
+                stats.ammo.push(AmmoStats {
+                    item_id: item.item_id.clone(),
+                    inventory_index: index,
+                    material: def.material.into(),
+                    rounds: def.rounds,
+                });
+            } else if let Some(consumable) = &def.consumable {
+                stats.consumables.push(ConsumableStats {
+                    item_id: item.item_id.clone(),
+                    inventory_index: index,
+                    definition: consumable.clone(),
+                    potency: 1.0 + item_stats.potency,
+                });
             }
         }
     }
+    // Gear speed drives every weapon's meter
+    for weapon in &mut stats.weapons {
+        weapon.speed += stats.speed;
+    }
+    stats
+}
+
+/// Stats of each item in `inventory.items` (same order) after synergies.
+/// `BuffSelf` lands on the source item, `BuffTarget` on the item at the synergy offset.
+/// Stored items get default stats and take no part in synergies.
+pub fn calculate_item_stats(
+    inventory: &PersistentInventory,
+    db: &ItemDatabase,
+) -> Vec<ItemStats> {
+    // 1. Reconstruct grid to calculate synergies
+    // PersistentInventory is just a list, so rebuild which item sits in each cell
+    // and which bag provides it (same projection as InventoryGridState::rebuild).
+    // Map: GridPos -> index into inventory.items
+    let mut grid_map: HashMap<IVec2, usize> = HashMap::new();
+    // Map: GridPos -> type of the bag providing the slot
+    let mut slot_map: HashMap<IVec2, BagType> = HashMap::new();
 
-    // 2. Iterate items and apply base stats + synergies
-    for item in &inventory.items {
+    for (index, item) in inventory.items.iter().enumerate().filter(|(_, item)| !item.in_storage) {
         if let Some(def) = db.items.get(&item.item_id) {
-            // Base Stats
-            stats.attack += def.attack;
-            stats.defense += def.defense;
-            stats.speed += def.speed;
-            // stats.health += def.health; // If added to ItemDefinition
-
-            // Synergies
-            for synergy in &def.synergies {
-                // Calculate target position based on rotation
-                // Synergy offset is relative to the item's pivot (0,0)
-                // We rotate the synergy offset vector by the item's rotation
-                let rotated_offset = rotate_vector(synergy.offset, item.rotation);
-                let target_pos = IVec2::new(item.grid_x, item.grid_y) + rotated_offset;
-
-                if let Some(target_item) = grid_map.get(&target_pos) {
-                     if let Some(target_def) = db.items.get(&target_item.item_id) {
-                         // Check tags
-                         let match_found = synergy.target_tags.iter().any(|tag| target_def.tags.contains(tag));
-
-                         if match_found {
-                             match &synergy.effect {
-                                 SynergyEffect::BuffSelf { stat, value } => {
-                                     apply_stat_bonus(&mut stats, *stat, *value);
-                                 }
-                                 SynergyEffect::BuffTarget { stat: _, value: _ } => {
-                                     // This is trickier because we are iterating linearly.
-                                     // "BuffTarget" implies the target gets stats.
-                                     // In a "Snapshot" calculation, we can just add to global stats
-                                     // UNLESS the buff is specific to the item (like "this sword deals +5").
-                                     // For global player stats, it doesn't matter who gets the buff.
-                                     // So we just apply it to the global sum.
-                                     // apply_stat_bonus(&mut stats, *stat, *value);
-
-                                     // Wait, if I have 2 swords, and one gets +5 attack, total attack is +5.
-                                     // So yes, just add to global.
-                                     // NOTE: If we wanted per-weapon stats (e.g. multi-attack), we'd need a different return structure.
-                                 }
-                                 SynergyEffect::BagBonus { bag_type: _, stat: _, value: _ } => {
-                                     // Check if item is inside the specific bag
-                                     // This requires checking "Slots" which we didn't fully reconstruct in this lightweight pass.
-                                     // However, we can check if the underlying slot is provided by a bag of that type.
-                                     // But `grid_map` here only maps Items. We need a Slot map.
-                                     // For now, skip or implement fully if needed.
-                                 }
-                             }
-                         }
-                     }
+            for pos in saved_item_cells(item, &def.shape) {
+                // Bags only provide slots, they don't occupy them
+                if let ItemType::Bag { bag_type } = def.item_type {
+                    slot_map.insert(pos, bag_type);
+                } else {
+                    grid_map.insert(pos, index);
                 }
             }
         }
     }
 
-    stats
+    // 2. Base stats
+    let mut per_item: Vec<ItemStats> = inventory.items.iter()
+        .map(|item| match db.items.get(&item.item_id) {
+            Some(def) if !item.in_storage => ItemStats {
+                attack: def.attack,
+                defense: def.defense,
+                speed: def.speed,
+                health: def.health,
+                on_hit: def.on_hit.clone(),
+                potency: 0.0,
+            },
+            _ => ItemStats::default(),
+        })
+        .collect();
+
+    // 3. Synergies
+    for (index, item) in inventory.items.iter().enumerate().filter(|(_, item)| !item.in_storage) {
+        let Some(def) = db.items.get(&item.item_id) else { continue; };
+
+        // Item at the synergy offset, if it has one of the target tags
+        let synergy_target = |synergy: &SynergyDefinition| -> Option<usize> {
+            // Synergy offset is relative to the item's pivot (0,0) and mirrors and rotates with the item
+            let rotated_offset = rotate_vector(synergy.offset, item.rotation, item.flipped);
+            let target_pos = IVec2::new(item.grid_x, item.grid_y) + rotated_offset;
+
+            let target_index = *grid_map.get(&target_pos)?;
+            // Multi-cell items can't buff themselves
+            if target_index == index { return None; }
+            let target_def = db.items.get(&inventory.items[target_index].item_id)?;
+
+            // Check tags
+            synergy.target_tags.iter().any(|tag| target_def.tags.contains(tag)).then_some(target_index)
+        };
+
+        for synergy in &def.synergies {
+            match &synergy.effect {
+                SynergyEffect::BuffSelf { stat, value } => {
+                    if synergy_target(synergy).is_some() {
+                        apply_stat_bonus(&mut per_item[index], *stat, *value);
+                    }
+                }
+                SynergyEffect::BuffTarget { stat, value } => {
+                    if let Some(target_index) = synergy_target(synergy) {
+                        apply_stat_bonus(&mut per_item[target_index], *stat, *value);
+                    }
+                }
+                SynergyEffect::AddOnHit { status } => {
+                    if let Some(target_index) = synergy_target(synergy) {
+                        per_item[target_index].on_hit.push(*status);
+                    }
+                }
+                SynergyEffect::BagBonus { bag_type, stat, value, coverage } => {
+                    let cells = saved_item_cells(item, &def.shape);
+                    let in_bag = |pos: &IVec2| slot_map.get(pos) == Some(bag_type);
+                    let applies = match coverage {
+                        BagCoverage::All => cells.iter().all(in_bag),
+                        BagCoverage::Any => cells.iter().any(in_bag),
+                    };
+                    if applies && !cells.is_empty() {
+                        apply_stat_bonus(&mut per_item[index], *stat, *value);
+                    }
+                }
+            }
+        }
+    }
+
+    per_item
 }
 
-fn apply_stat_bonus(stats: &mut CombatStats, stat: StatType, value: f32) {
+/// Ammo items touching one of the weapon's cells or sitting in a bag the weapon also occupies.
+fn ammo_in_reach(
+    weapon: usize,
+    cells: &[Vec<IVec2>],
+    bag_of: &HashMap<IVec2, usize>,
+    is_ammo: impl Fn(usize) -> bool,
+) -> Vec<usize> {
+    let weapon_cells = &cells[weapon];
+    let weapon_bags: HashSet<usize> = weapon_cells.iter().filter_map(|pos| bag_of.get(pos).copied()).collect();
+    let touches = |pos: &IVec2| weapon_cells.iter().any(|w| (*w - *pos).abs().element_sum() == 1);
+    let shares_bag = |pos: &IVec2| bag_of.get(pos).is_some_and(|bag| weapon_bags.contains(bag));
+
+    (0..cells.len())
+        .filter(|&index| index != weapon && is_ammo(index))
+        .filter(|&index| cells[index].iter().any(|pos| touches(pos) || shares_bag(pos)))
+        .collect()
+}
+
+/// Absolute cells covered by a saved item.
+fn saved_item_cells(item: &SavedItem, shape: &[IVec2]) -> Vec<IVec2> {
+    crate::plugins::inventory::orient_shape(shape, item.rotation, item.flipped)
+        .into_iter()
+        .map(|offset| IVec2::new(item.grid_x, item.grid_y) + offset)
+        .collect()
+}
+
+fn apply_stat_bonus(stats: &mut ItemStats, stat: StatType, value: f32) {
     match stat {
         StatType::Attack => stats.attack += value,
         StatType::Defense => stats.defense += value,
         StatType::Speed => stats.speed += value,
         StatType::Health => stats.health += value,
+        StatType::Potency => stats.potency += value,
     }
 }
 
 // Helper to rotate a single vector (same logic as in inventory.rs but for single vec)
-fn rotate_vector(p: IVec2, rot: u8) -> IVec2 {
+// A flipped item mirrors the vector left to right first, like `orient_shape`
+fn rotate_vector(p: IVec2, rot: u8, flipped: bool) -> IVec2 {
     let turns = rot % 4;
-    let mut v = p;
+    let mut v = if flipped { IVec2::new(-p.x, p.y) } else { p };
     for _ in 0..turns {
         v = IVec2::new(-v.y, v.x);
     }
     v
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::items::{ItemDefinition, ItemTag, generate_default_shape};
+
+    fn weapon(id: &str, width: u8, height: u8, attack: f32) -> ItemDefinition {
+        let mut def = ItemDefinition {
+            id: id.to_string(),
+            name: id.to_string(),
+            width,
+            height,
+            tags: vec![ItemTag::Weapon],
+            attack,
+            ..default()
+        };
+        generate_default_shape(&mut def);
+        def
+    }
+
+    /// Whetstone that only buffs the item to its right (before rotation)
+    fn right_whetstone() -> ItemDefinition {
+        let mut def = weapon("right_whetstone", 1, 1, 0.0);
+        def.item_type = ItemType::Consumable;
+        def.tags = vec![ItemTag::Valuable];
+        def.synergies.push(SynergyDefinition {
+            offset: IVec2::new(1, 0),
+            target_tags: vec![ItemTag::Weapon],
+            effect: SynergyEffect::BuffTarget { stat: StatType::Attack, value: 5.0 },
+            visual_type: default(),
+        });
+        def
+    }
+
+    fn db_with(defs: Vec<ItemDefinition>) -> ItemDatabase {
+        let mut db = ItemDatabase::default();
+        for def in defs {
+            db.items.insert(def.id.clone(), def);
+        }
+        db
+    }
+
+    #[test]
+    fn test_buff_target_follows_rotation() {
+        let db = db_with(vec![weapon("sword", 1, 2, 10.0), right_whetstone()]);
+
+        // Unrotated: buffs (1,0), the sword's top cell
+        let inventory = PersistentInventory { items: vec![SavedItem::new("right_whetstone", 0, 0, 0), SavedItem::new("sword", 1, 0, 0)] };
+        let per_item = calculate_item_stats(&inventory, &db);
+        assert_eq!(per_item[1].attack, 15.0);
+        assert_eq!(per_item[0].attack, 0.0);
+
+        // Rotated once: (1,0) -> (0,1), which is now below the whetstone
+        let inventory = PersistentInventory { items: vec![SavedItem::new("right_whetstone", 0, 0, 1), SavedItem::new("sword", 1, 0, 0)] };
+        assert_eq!(calculate_item_stats(&inventory, &db)[1].attack, 10.0);
+
+        let inventory = PersistentInventory { items: vec![SavedItem::new("right_whetstone", 0, 0, 1), SavedItem::new("sword", 0, 1, 0)] };
+        assert_eq!(calculate_item_stats(&inventory, &db)[1].attack, 15.0);
+        assert_eq!(calculate_combat_stats(&inventory, &db).attack, 15.0);
+
+        // A stored whetstone buffs nothing, and a stored sword isn't brought along
+        let mut inventory = inventory;
+        inventory.items[0].in_storage = true;
+        assert_eq!(calculate_item_stats(&inventory, &db)[1].attack, 10.0);
+        inventory.items[1].in_storage = true;
+        let stats = calculate_combat_stats(&inventory, &db);
+        assert_eq!((stats.attack, stats.weapons.len()), (0.0, 0));
+    }
+
+    #[test]
+    fn test_buff_target_follows_mirroring() {
+        let db = db_with(vec![weapon("sword", 1, 2, 10.0), right_whetstone()]);
+        let flipped = |x, y, rotation| SavedItem { flipped: true, ..SavedItem::new("right_whetstone", x, y, rotation) };
+
+        // Mirrored, the whetstone points left
+        let inventory = PersistentInventory { items: vec![flipped(1, 0, 0), SavedItem::new("sword", 0, 0, 0)] };
+        assert_eq!(calculate_item_stats(&inventory, &db)[1].attack, 15.0);
+        let inventory = PersistentInventory { items: vec![flipped(0, 0, 0), SavedItem::new("sword", 1, 0, 0)] };
+        assert_eq!(calculate_item_stats(&inventory, &db)[1].attack, 10.0);
+
+        // Mirrored then turned once: (1,0) -> (-1,0) -> (0,-1), above the whetstone
+        let inventory = PersistentInventory { items: vec![flipped(0, 2, 1), SavedItem::new("sword", 0, 0, 0)] };
+        assert_eq!(calculate_item_stats(&inventory, &db)[1].attack, 15.0);
+    }
+
+    #[test]
+    fn test_bag_bonus_coverage() {
+        let mut belt = weapon("potion_belt", 2, 1, 0.0);
+        belt.item_type = ItemType::Bag { bag_type: BagType::PotionBelt };
+        let mut bag = weapon("leather_bag", 2, 2, 0.0);
+        bag.item_type = ItemType::Bag { bag_type: BagType::Leather };
+
+        let bonus = |coverage| SynergyDefinition {
+            offset: IVec2::ZERO,
+            target_tags: vec![],
+            effect: SynergyEffect::BagBonus { bag_type: BagType::PotionBelt, stat: StatType::Speed, value: 3.0, coverage },
+            visual_type: default(),
+        };
+        let mut all_sword = weapon("all_sword", 2, 1, 0.0);
+        all_sword.synergies.push(bonus(BagCoverage::All));
+        let mut any_sword = weapon("any_sword", 2, 1, 0.0);
+        any_sword.synergies.push(bonus(BagCoverage::Any));
+
+        let db = db_with(vec![belt, bag, all_sword, any_sword]);
+
+        // Belt at (0,0)-(1,0), leather bag below it at (0,1)-(1,2)
+        let bags = vec![SavedItem::new("potion_belt", 0, 0, 0), SavedItem::new("leather_bag", 0, 1, 0)];
+
+        // Fully on the belt: both apply
+        let mut items = bags.clone();
+        items.extend([SavedItem::new("all_sword", 0, 0, 0)]);
+        let inventory = PersistentInventory { items };
+        assert_eq!(calculate_item_stats(&inventory, &db)[2].speed, 3.0);
+
+        // Rotated to straddle belt (0,0) and leather (0,1): only Any applies
+        let mut items = bags.clone();
+        items.extend([SavedItem::new("all_sword", 0, 0, 1)]);
+        assert_eq!(calculate_item_stats(&PersistentInventory { items }, &db)[2].speed, 0.0);
+
+        let mut items = bags;
+        items.extend([SavedItem::new("any_sword", 0, 0, 1)]);
+        assert_eq!(calculate_item_stats(&PersistentInventory { items }, &db)[2].speed, 3.0);
+    }
+
+    #[test]
+    fn test_weapon_breakdown() {
+        let mut dagger = weapon("dagger", 1, 1, 8.0);
+        dagger.material = crate::plugins::items::MaterialType::Silver;
+        dagger.speed = 5.0;
+        let db = db_with(vec![weapon("sword", 1, 2, 10.0), dagger, right_whetstone()]);
+
+        let inventory = PersistentInventory {
+            items: vec![
+                SavedItem::new("right_whetstone", 0, 0, 0),
+                SavedItem::new("sword", 1, 0, 0),
+                SavedItem::new("dagger", 2, 0, 0),
+            ],
+        };
+        let stats = calculate_combat_stats(&inventory, &db);
+
+        assert_eq!(stats.attack, 23.0);
+        assert_eq!(stats.weapons.len(), 2);
+        assert_eq!(stats.weapons[0].item_id, "sword");
+        assert_eq!(stats.weapons[0].damage, 15.0);
+        assert_eq!(stats.weapons[0].material, MaterialType::Steel);
+        assert_eq!(stats.weapons[1].inventory_index, 2);
+        assert_eq!(stats.weapons[1].material, MaterialType::Silver);
+        assert_eq!(stats.weapons[1].cooldown_ticks(), 1000.0 / 15.0);
+    }
+
+    #[test]
+    fn test_armor_speed_slows_every_weapon() {
+        let mut dagger = weapon("dagger", 1, 1, 8.0);
+        dagger.speed = 5.0;
+        let mut mail = weapon("mail", 1, 1, 0.0);
+        mail.item_type = ItemType::Armor;
+        mail.speed = -2.0;
+        let db = db_with(vec![weapon("sword", 1, 2, 10.0), dagger, mail]);
+
+        let inventory = PersistentInventory {
+            items: vec![SavedItem::new("sword", 0, 0, 0), SavedItem::new("dagger", 1, 0, 0), SavedItem::new("mail", 2, 0, 0)],
+        };
+        let stats = calculate_combat_stats(&inventory, &db);
+        assert_eq!(stats.speed, -2.0);
+        let speeds: Vec<f32> = stats.weapons.iter().map(|w| w.speed).collect();
+        assert_eq!(speeds, vec![-2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_ranged_weapon_reaches_adjacent_and_same_bag_ammo() {
+        let mut bow = weapon("bow", 1, 1, 10.0);
+        bow.ranged = Some(RangedDefinition::default());
+        let mut arrows = weapon("arrows", 1, 1, 0.0);
+        arrows.item_type = ItemType::Ammo;
+        arrows.material = crate::plugins::items::MaterialType::Silver;
+        arrows.rounds = 5;
+        let mut bag = weapon("bag", 3, 3, 0.0);
+        bag.item_type = ItemType::Bag { bag_type: BagType::Leather };
+        let db = db_with(vec![bow, arrows, bag]);
+
+        let inventory = PersistentInventory {
+            items: vec![
+                SavedItem::new("bag", 0, 0, 0),
+                SavedItem::new("bow", 0, 0, 0),
+                // Same bag, not touching
+                SavedItem::new("arrows", 2, 2, 0),
+                // Outside the bag, touching
+                SavedItem::new("arrows", -1, 0, 0),
+                // Neither
+                SavedItem::new("arrows", 5, 5, 0),
+            ],
+        };
+        let stats = calculate_combat_stats(&inventory, &db);
+
+        assert_eq!(stats.weapons[0].ammo, vec![2, 3]);
+        assert_eq!(stats.ammo.len(), 3);
+        assert_eq!(stats.ammo[0].material, MaterialType::Silver);
+        assert_eq!(stats.ammo[0].rounds, 5);
+    }
+
+    #[test]
+    fn test_multiple_sources_stack_on_one_weapon() {
+        let db = db_with(vec![weapon("sword", 1, 2, 10.0), right_whetstone()]);
+
+        // Whetstones left of both sword cells, each pointing right
+        let inventory = PersistentInventory {
+            items: vec![
+                SavedItem::new("sword", 1, 0, 0),
+                SavedItem::new("right_whetstone", 0, 0, 0),
+                SavedItem::new("right_whetstone", 0, 1, 0),
+            ],
+        };
+        let per_item = calculate_item_stats(&inventory, &db);
+        assert_eq!(per_item[0].attack, 20.0);
+        assert_eq!(calculate_combat_stats(&inventory, &db).attack, 20.0);
+    }
+}
@@ -0,0 +1,293 @@
+use crate::plugins::items::{ItemDatabase, ItemDatabaseReloaded, ItemDefinition, ItemTag, ItemType, StatusApplication, SynergyEffect};
+use crate::plugins::combat::PLAYER_BASE_HEALTH;
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use std::fmt;
+
+/// Which part of a recipe referenced an item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecipeRole {
+    Ingredient,
+    Result,
+    Catalyst,
+}
+
+/// A single problem found in the item database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationIssue {
+    /// Recipe (by index in `ItemDatabase::recipes`) references an item id that doesn't exist
+    UnknownRecipeItem { recipe: usize, role: RecipeRole, item_id: String },
+    EmptyShape { item_id: String },
+    /// Bag with an empty shape; a bag's shape is the slots it provides
+    BagWithoutSlots { item_id: String },
+    /// Shape cell lies outside the item's `width` x `height` box
+    ShapeOutOfBounds { item_id: String, cell: IVec2 },
+    /// Shape cells don't form a single 4-connected piece
+    DisconnectedShape { item_id: String },
+    /// Synergy offset points at one of the item's own cells, so it can never find a neighbour
+    SynergyInsideShape { item_id: String, offset: IVec2 },
+    /// On-hit status (own or granted by a synergy) with no stacks, no duration or a chance outside 0..=1
+    InvalidStatus { item_id: String },
+    AmmoWithoutRounds { item_id: String },
+    /// Potion or food consumable with no `consumable` block, so it never fires in a fight
+    ConsumableWithoutEffect { item_id: String },
+    /// `ranged` set on something that isn't a weapon, or an empty-ammo damage outside 0..=1
+    InvalidRanged { item_id: String },
+    /// Health bonus that isn't finite or would take the whole base health on its own
+    InvalidHealth { item_id: String },
+    /// Armor with neither defense nor health
+    ArmorWithoutProtection { item_id: String },
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationIssue::UnknownRecipeItem { recipe, role, item_id } => {
+                write!(f, "recipe #{}: {:?} '{}' is not a known item", recipe, role, item_id)
+            }
+            ValidationIssue::EmptyShape { item_id } => {
+                write!(f, "item '{}': shape is empty", item_id)
+            }
+            ValidationIssue::BagWithoutSlots { item_id } => {
+                write!(f, "bag '{}' provides no slots", item_id)
+            }
+            ValidationIssue::ShapeOutOfBounds { item_id, cell } => {
+                write!(f, "item '{}': shape cell {} is outside its width x height", item_id, cell)
+            }
+            ValidationIssue::DisconnectedShape { item_id } => {
+                write!(f, "item '{}': shape is not connected", item_id)
+            }
+            ValidationIssue::SynergyInsideShape { item_id, offset } => {
+                write!(f, "item '{}': synergy offset {} is inside the item's own shape", item_id, offset)
+            }
+            ValidationIssue::InvalidStatus { item_id } => {
+                write!(f, "item '{}': on-hit status needs stacks, a duration and a chance in 0..=1", item_id)
+            }
+            ValidationIssue::AmmoWithoutRounds { item_id } => {
+                write!(f, "ammo '{}' holds no rounds", item_id)
+            }
+            ValidationIssue::ConsumableWithoutEffect { item_id } => {
+                write!(f, "consumable '{}' has no trigger or effects", item_id)
+            }
+            ValidationIssue::InvalidRanged { item_id } => {
+                write!(f, "item '{}': only weapons can be ranged, with empty_damage in 0..=1", item_id)
+            }
+            ValidationIssue::InvalidHealth { item_id } => {
+                write!(f, "item '{}': health must be finite and above -{}", item_id, PLAYER_BASE_HEALTH)
+            }
+            ValidationIssue::ArmorWithoutProtection { item_id } => {
+                write!(f, "armor '{}' gives neither defense nor health", item_id)
+            }
+        }
+    }
+}
+
+/// Checks the whole database and returns every problem found (empty when valid).
+/// Items are visited in id order so the report is stable.
+pub fn validate_item_database(db: &ItemDatabase) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+
+    let mut ids: Vec<&String> = db.items.keys().collect();
+    ids.sort();
+    for id in ids {
+        validate_item(&db.items[id], &mut issues);
+    }
+
+    for (index, recipe) in db.recipes.iter().enumerate() {
+        let refs = recipe.ingredients.iter().map(|id| (RecipeRole::Ingredient, id))
+            .chain(std::iter::once((RecipeRole::Result, &recipe.result)))
+            .chain(recipe.catalysts.iter().map(|id| (RecipeRole::Catalyst, id)));
+
+        for (role, item_id) in refs {
+            if !db.items.contains_key(item_id) {
+                issues.push(ValidationIssue::UnknownRecipeItem { recipe: index, role, item_id: item_id.clone() });
+            }
+        }
+    }
+
+    issues
+}
+
+fn validate_item(def: &ItemDefinition, issues: &mut Vec<ValidationIssue>) {
+    let item_id = def.id.clone();
+
+    if def.shape.is_empty() {
+        if matches!(def.item_type, ItemType::Bag { .. }) {
+            issues.push(ValidationIssue::BagWithoutSlots { item_id });
+        } else {
+            issues.push(ValidationIssue::EmptyShape { item_id });
+        }
+        return;
+    }
+
+    for cell in &def.shape {
+        if cell.x < 0 || cell.y < 0 || cell.x >= def.width as i32 || cell.y >= def.height as i32 {
+            issues.push(ValidationIssue::ShapeOutOfBounds { item_id: item_id.clone(), cell: *cell });
+        }
+    }
+
+    if !is_connected(&def.shape) {
+        issues.push(ValidationIssue::DisconnectedShape { item_id: item_id.clone() });
+    }
+
+    for synergy in &def.synergies {
+        // Bag bonuses don't look at a neighbour, so their offset is unused
+        if matches!(synergy.effect, SynergyEffect::BagBonus { .. }) { continue; }
+        if def.shape.contains(&synergy.offset) {
+            issues.push(ValidationIssue::SynergyInsideShape { item_id: item_id.clone(), offset: synergy.offset });
+        }
+    }
+
+    let granted = def.synergies.iter().filter_map(|s| match &s.effect {
+        SynergyEffect::AddOnHit { status } => Some(status),
+        _ => None,
+    });
+    if def.on_hit.iter().chain(granted).any(|s| !is_valid_status(s)) {
+        issues.push(ValidationIssue::InvalidStatus { item_id: item_id.clone() });
+    }
+
+    if def.item_type == ItemType::Ammo && def.rounds == 0 {
+        issues.push(ValidationIssue::AmmoWithoutRounds { item_id: item_id.clone() });
+    }
+    // Trinkets like the whetstone are consumables too, but only potions and food get used up
+    let is_drunk_or_eaten = def.tags.iter().any(|t| matches!(t, ItemTag::Potion | ItemTag::Food));
+    if def.item_type == ItemType::Consumable && is_drunk_or_eaten && def.consumable.is_none() {
+        issues.push(ValidationIssue::ConsumableWithoutEffect { item_id: item_id.clone() });
+    }
+    if let Some(ranged) = def.ranged {
+        if def.item_type != ItemType::Weapon || !(0.0..=1.0).contains(&ranged.empty_damage) {
+            issues.push(ValidationIssue::InvalidRanged { item_id: item_id.clone() });
+        }
+    }
+
+    if !def.health.is_finite() || def.health <= -PLAYER_BASE_HEALTH {
+        issues.push(ValidationIssue::InvalidHealth { item_id: item_id.clone() });
+    }
+    if def.item_type == ItemType::Armor && def.defense <= 0.0 && def.health <= 0.0 {
+        issues.push(ValidationIssue::ArmorWithoutProtection { item_id });
+    }
+}
+
+fn is_valid_status(status: &StatusApplication) -> bool {
+    status.stacks > 0 && status.duration > 0 && (0.0..=1.0).contains(&status.chance)
+}
+
+/// Flood fill from the first cell; connected if every cell is reached.
+fn is_connected(shape: &[IVec2]) -> bool {
+    let cells: HashSet<IVec2> = shape.iter().copied().collect();
+    let Some(&start) = shape.first() else { return true; };
+
+    let mut visited = HashSet::new();
+    let mut stack = vec![start];
+    while let Some(cell) = stack.pop() {
+        if !visited.insert(cell) { continue; }
+        for dir in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y] {
+            let next = cell + dir;
+            if cells.contains(&next) && !visited.contains(&next) {
+                stack.push(next);
+            }
+        }
+    }
+
+    visited.len() == cells.len()
+}
+
+/// Logs validation problems after the database is loaded or reloaded.
+pub fn report_item_issues(db: &ItemDatabase) {
+    let issues = validate_item_database(db);
+    if issues.is_empty() {
+        info!("ItemDatabase validation passed.");
+        return;
+    }
+    warn!("ItemDatabase validation found {} problem(s):", issues.len());
+    for issue in &issues {
+        warn!("  {}", issue);
+    }
+}
+
+pub(crate) fn validate_on_startup(item_db: Res<ItemDatabase>) {
+    report_item_issues(&item_db);
+}
+
+pub(crate) fn validate_on_reload(mut ev_reloaded: EventReader<ItemDatabaseReloaded>, item_db: Res<ItemDatabase>) {
+    if ev_reloaded.read().count() > 0 {
+        report_item_issues(&item_db);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::items::{BagType, StatType, SynergyDefinition};
+    use crate::plugins::status_effects::StatusKind;
+
+    fn item(id: &str, width: u8, height: u8, shape: Vec<IVec2>) -> ItemDefinition {
+        ItemDefinition {
+            id: id.to_string(),
+            name: id.to_string(),
+            width,
+            height,
+            shape,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn test_detects_shape_problems() {
+        let mut db = ItemDatabase::default();
+
+        // L-shape, valid
+        let ok = item("ok", 2, 2, vec![IVec2::new(0, 0), IVec2::new(0, 1), IVec2::new(1, 1)]);
+        let split = item("split", 3, 1, vec![IVec2::new(0, 0), IVec2::new(2, 0)]);
+        let outside = item("outside", 1, 1, vec![IVec2::new(0, 0), IVec2::new(1, 0)]);
+        let empty = item("empty", 1, 1, vec![]);
+        let mut bag = item("bag", 1, 1, vec![]);
+        bag.item_type = ItemType::Bag { bag_type: BagType::Leather };
+        let mut selfish = item("selfish", 2, 1, vec![IVec2::new(0, 0), IVec2::new(1, 0)]);
+        selfish.synergies.push(SynergyDefinition {
+            offset: IVec2::new(1, 0),
+            target_tags: vec![ItemTag::Weapon],
+            effect: SynergyEffect::BuffSelf { stat: StatType::Attack, value: 1.0 },
+            visual_type: default(),
+        });
+
+        let mut bleeder = item("bleeder", 1, 1, vec![IVec2::ZERO]);
+        bleeder.on_hit.push(StatusApplication { kind: StatusKind::Bleed, stacks: 0, duration: 10, chance: 1.0 });
+
+        let mut dud = item("dud", 1, 1, vec![IVec2::ZERO]);
+        dud.item_type = ItemType::Ammo;
+        let mut flask = item("flask", 1, 1, vec![IVec2::ZERO]);
+        flask.item_type = ItemType::Consumable;
+        flask.tags.push(ItemTag::Potion);
+
+        let mut cursed = item("cursed", 1, 1, vec![IVec2::ZERO]);
+        cursed.health = -PLAYER_BASE_HEALTH;
+        let mut rags = item("rags", 1, 1, vec![IVec2::ZERO]);
+        rags.item_type = ItemType::Armor;
+
+        for def in [ok, split, outside, empty, bag, selfish, bleeder, dud, flask, cursed, rags] {
+            db.items.insert(def.id.clone(), def);
+        }
+
+        let issues = validate_item_database(&db);
+        assert_eq!(issues, vec![
+            ValidationIssue::BagWithoutSlots { item_id: "bag".into() },
+            ValidationIssue::InvalidStatus { item_id: "bleeder".into() },
+            ValidationIssue::InvalidHealth { item_id: "cursed".into() },
+            ValidationIssue::AmmoWithoutRounds { item_id: "dud".into() },
+            ValidationIssue::EmptyShape { item_id: "empty".into() },
+            ValidationIssue::ConsumableWithoutEffect { item_id: "flask".into() },
+            ValidationIssue::ShapeOutOfBounds { item_id: "outside".into(), cell: IVec2::new(1, 0) },
+            ValidationIssue::ArmorWithoutProtection { item_id: "rags".into() },
+            ValidationIssue::SynergyInsideShape { item_id: "selfish".into(), offset: IVec2::new(1, 0) },
+            ValidationIssue::DisconnectedShape { item_id: "split".into() },
+        ]);
+    }
+
+    #[test]
+    fn test_shipped_assets_are_valid() {
+        let db = ItemDatabase::shipped();
+        let issues = validate_item_database(&db);
+        assert!(issues.is_empty(), "unexpected issues: {:?}", issues);
+    }
+}
@@ -1,8 +1,15 @@
 use bevy::prelude::*;
 use bevy::utils::HashMap;
 use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use crate::plugins::combat::TargetingStrategy;
+use crate::plugins::item_validation::{validate_on_reload, validate_on_startup};
+use crate::plugins::status_effects::StatusKind;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::hash::{DefaultHasher, Hash, Hasher};
 
-#[derive(Resource, Default)]
+#[derive(Resource, Default, Debug)]
 pub struct ItemDatabase {
     pub items: HashMap<String, ItemDefinition>,
     pub recipes: Vec<RecipeDefinition>,
@@ -50,6 +57,110 @@ pub struct ItemDefinition {
     pub defense: f32,
     #[serde(default)]
     pub speed: f32,
+    /// Added to the player's max health
+    #[serde(default)]
+    pub health: f32,
+
+    /// Which enemy a weapon aims at
+    #[serde(default)]
+    pub targeting: TargetingStrategy,
+
+    /// Statuses a weapon inflicts on hit
+    #[serde(default)]
+    pub on_hit: Vec<StatusApplication>,
+
+    /// What a consumable does during a fight
+    #[serde(default)]
+    pub consumable: Option<ConsumableDefinition>,
+
+    /// Makes a weapon ranged: every attack draws one round of ammo
+    #[serde(default)]
+    pub ranged: Option<RangedDefinition>,
+
+    /// Rounds an `Ammo` item holds per fight
+    #[serde(default)]
+    pub rounds: u32,
+}
+
+impl ItemDefinition {
+    /// Non-zero base stats, e.g. ["Attack +10", "Health +25"], for tooltips.
+    pub fn stat_lines(&self) -> Vec<String> {
+        let stats = [("Attack", self.attack), ("Defense", self.defense), ("Speed", self.speed), ("Health", self.health)];
+        let mut parts: Vec<String> = stats.iter()
+            .filter(|(_, value)| *value != 0.0)
+            .map(|(name, value)| format!("{} {:+}", name, value))
+            .collect();
+        if self.ranged.is_some() {
+            parts.push("Ranged".to_string());
+        }
+        if self.item_type == ItemType::Ammo {
+            parts.push(format!("{} rounds", self.rounds));
+        }
+        parts
+    }
+}
+
+/// A ranged weapon draws from `Ammo` items next to it or in the same bag,
+/// and hits with the ammo's material instead of its own.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Default)]
+pub struct RangedDefinition {
+    /// Fraction of attack still dealt with no ammo left; 0 means the weapon stops attacking
+    #[serde(default)]
+    pub empty_damage: f32,
+}
+
+/// A consumable's behaviour in combat: when `trigger` fires, all `effects` apply and one use is spent.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ConsumableDefinition {
+    pub trigger: ConsumableTrigger,
+    pub effects: Vec<ConsumableEffect>,
+    #[serde(default = "default_uses")]
+    pub uses: u32,
+}
+
+fn default_uses() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum ConsumableTrigger {
+    /// Holder's health drops below this fraction of max
+    HealthBelow { fraction: f32 },
+    /// First combat tick
+    FightStart,
+    /// Every `ticks` combat ticks
+    Every { ticks: u32 },
+}
+
+/// Effect amounts are scaled by the item's potency.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum ConsumableEffect {
+    Heal { amount: f32 },
+    /// Flat bonus to Attack, Defense or Speed for `duration` ticks
+    Buff { stat: StatType, value: f32, duration: u32 },
+    /// Removes all statuses
+    Cleanse,
+    /// Puts a status on the holder, e.g. Regeneration; potency scales the stacks
+    GainStatus { kind: StatusKind, stacks: u32, duration: u32 },
+}
+
+/// A status inflicted on hit: `stacks` for `duration` ticks, with probability `chance`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct StatusApplication {
+    pub kind: StatusKind,
+    #[serde(default = "default_stacks")]
+    pub stacks: u32,
+    pub duration: u32,
+    #[serde(default = "default_chance")]
+    pub chance: f32,
+}
+
+fn default_stacks() -> u32 {
+    1
+}
+
+fn default_chance() -> f32 {
+    1.0
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Hash, PartialOrd, Ord)]
@@ -75,6 +186,7 @@ pub enum ItemTag {
     Food,
     Magic,
     Valuable,
+    Armor,
     // Add more as needed
 }
 
@@ -82,8 +194,10 @@ pub enum ItemTag {
 pub struct SynergyDefinition {
     // Relative coordinate from item pivot (0,0)
     // Note: This needs to rotate with the item
+    #[serde(default)]
     pub offset: IVec2,
     // If the item at 'offset' has ANY of these tags, the effect triggers
+    #[serde(default)]
     pub target_tags: Vec<ItemTag>,
     pub effect: SynergyEffect,
     #[serde(default)]
@@ -111,19 +225,36 @@ pub enum SynergyEffect {
         value: f32,
     },
     // Bonus for BEING inside a specific bag type
+    // (offset and target_tags are ignored for this effect and can be left out)
     BagBonus {
         bag_type: BagType,
         stat: StatType,
         value: f32,
+        #[serde(default)]
+        coverage: BagCoverage,
+    },
+    // TARGET item also inflicts this status on hit
+    AddOnHit {
+        status: StatusApplication,
     },
 }
 
+/// How much of an item must sit on slots of the bag type for a `BagBonus` to apply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Default)]
+pub enum BagCoverage {
+    #[default]
+    All,
+    Any,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Hash)]
 pub enum StatType {
     Attack,
     Defense,
     Speed,
     Health,
+    /// Consumable effect strength, as a fraction (0.5 = +50%)
+    Potency,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Default)]
@@ -142,6 +273,8 @@ pub enum ItemType {
     Weapon,
     Consumable,
     Ammo,
+    /// Worn for defense and health; never attacks
+    Armor,
     Bag { bag_type: BagType },
 }
 
@@ -155,271 +288,349 @@ pub enum BagType {
     FannyPack,
 }
 
+/// Directory scanned for `*.ron` item and recipe files.
+pub const ITEMS_ASSET_DIR: &str = "assets/items";
+
+/// Layout of a single item file. Both lists are optional so a file can hold
+/// only items, only recipes, or both.
+#[derive(Debug, Default, Deserialize)]
+pub struct ItemFile {
+    #[serde(default)]
+    pub items: Vec<ItemDefinition>,
+    #[serde(default)]
+    pub recipes: Vec<RecipeDefinition>,
+}
+
+#[derive(Debug)]
+pub enum ItemLoadError {
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// RON syntax or schema error. `item_id` is the id of the entry the error
+    /// points into, when it can be recovered from the source text.
+    Parse {
+        path: PathBuf,
+        item_id: Option<String>,
+        source: Box<ron::error::SpannedError>,
+    },
+    DuplicateId {
+        id: String,
+        first: PathBuf,
+        second: PathBuf,
+    },
+    InvalidItem {
+        path: PathBuf,
+        id: String,
+        reason: String,
+    },
+}
+
+impl std::fmt::Display for ItemLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ItemLoadError::Io { path, source } => {
+                write!(f, "{}: {}", path.display(), source)
+            }
+            ItemLoadError::Parse { path, item_id: Some(id), source } => {
+                write!(f, "{}: item '{}': {}", path.display(), id, source)
+            }
+            ItemLoadError::Parse { path, item_id: None, source } => {
+                write!(f, "{}: {}", path.display(), source)
+            }
+            ItemLoadError::DuplicateId { id, first, second } => {
+                write!(f, "{}: duplicate item id '{}' (first defined in {})", second.display(), id, first.display())
+            }
+            ItemLoadError::InvalidItem { path, id, reason } => {
+                write!(f, "{}: item '{}': {}", path.display(), id, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ItemLoadError {}
+
+impl ItemDatabase {
+    /// Loads every `*.ron` file in `dir` (sorted by file name) into a fresh database.
+    pub fn load_from_dir(dir: impl AsRef<Path>) -> Result<Self, ItemLoadError> {
+        let paths = ron_files_in(dir.as_ref())?;
+
+        let mut db = ItemDatabase::default();
+        // Item id -> file it came from, for duplicate reporting
+        let mut sources: HashMap<String, PathBuf> = HashMap::new();
+
+        for path in paths {
+            let file = load_item_file(&path)?;
+
+            for mut item in file.items {
+                if item.id.is_empty() {
+                    return Err(ItemLoadError::InvalidItem { path, id: item.name, reason: "missing id".to_string() });
+                }
+                if item.width == 0 || item.height == 0 {
+                    return Err(ItemLoadError::InvalidItem {
+                        path,
+                        id: item.id,
+                        reason: format!("size {}x{} must be at least 1x1", item.width, item.height),
+                    });
+                }
+                if let Some(first) = sources.get(&item.id) {
+                    return Err(ItemLoadError::DuplicateId { id: item.id, first: first.clone(), second: path });
+                }
+
+                generate_default_shape(&mut item);
+                sources.insert(item.id.clone(), path.clone());
+                db.items.insert(item.id.clone(), item);
+            }
+
+            db.recipes.extend(file.recipes);
+        }
+
+        Ok(db)
+    }
+}
+
+#[cfg(test)]
+impl ItemDatabase {
+    /// The item files shipped in `ITEMS_ASSET_DIR`.
+    pub(crate) fn shipped() -> Self {
+        Self::load_from_dir(Path::new(env!("CARGO_MANIFEST_DIR")).join(ITEMS_ASSET_DIR)).expect("item assets should load")
+    }
+}
+
+/// Reads and parses a single item file.
+pub fn load_item_file(path: &Path) -> Result<ItemFile, ItemLoadError> {
+    load_ron_file(path)
+}
+
+/// All `*.ron` files directly inside `dir`, sorted by file name.
+pub fn ron_files_in(dir: &Path) -> Result<Vec<PathBuf>, ItemLoadError> {
+    let entries = fs::read_dir(dir).map_err(|source| ItemLoadError::Io { path: dir.to_path_buf(), source })?;
+
+    let mut paths = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|source| ItemLoadError::Io { path: dir.to_path_buf(), source })?;
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "ron") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+/// Reads and parses any RON data file whose entries are keyed by an `id:` field.
+pub fn load_ron_file<T: DeserializeOwned>(path: &Path) -> Result<T, ItemLoadError> {
+    let text = fs::read_to_string(path).map_err(|source| ItemLoadError::Io { path: path.to_path_buf(), source })?;
+
+    ron::from_str::<T>(&text).map_err(|source| ItemLoadError::Parse {
+        path: path.to_path_buf(),
+        item_id: enclosing_item_id(&text, source.span.start.line),
+        source: Box::new(source),
+    })
+}
+
+/// Auto-generates a rectangular `width` x `height` shape if none was given.
+pub fn generate_default_shape(item: &mut ItemDefinition) {
+    if item.shape.is_empty() {
+        for y in 0..item.height {
+            for x in 0..item.width {
+                item.shape.push(IVec2::new(x as i32, y as i32));
+            }
+        }
+    }
+}
+
+/// Finds the last `id: "..."` at or above `line` (1-based), i.e. the entry a parse error sits in.
+fn enclosing_item_id(text: &str, line: usize) -> Option<String> {
+    text.lines()
+        .take(line)
+        .filter_map(|l| {
+            let rest = l.trim_start().strip_prefix("id:")?.trim_start().strip_prefix('"')?;
+            rest.split('"').next().map(str::to_string)
+        })
+        .last()
+}
+
+/// Polls the item directory (`ITEMS_ASSET_DIR` by default) for changes so balance edits apply without a restart.
+#[derive(Resource)]
+pub struct ItemHotReload {
+    pub dir: PathBuf,
+    pub timer: Timer,
+    /// Content hash of every item file at the last check
+    last_stamp: Option<HashMap<PathBuf, u64>>,
+}
+
+impl Default for ItemHotReload {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from(ITEMS_ASSET_DIR),
+            timer: Timer::from_seconds(1.0, TimerMode::Repeating),
+            last_stamp: None,
+        }
+    }
+}
+
+/// Sent after `ItemDatabase` has been replaced by a successful reload.
+#[derive(Event)]
+pub struct ItemDatabaseReloaded;
+
 pub struct ItemsPlugin;
 
 impl Plugin for ItemsPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<ItemDatabase>()
-           .add_systems(Startup, load_items);
+           .init_resource::<ItemHotReload>()
+           .add_event::<ItemDatabaseReloaded>()
+           .add_systems(Startup, (load_items, validate_on_startup).chain())
+           .add_systems(Update, (hot_reload_items, validate_on_reload).chain());
     }
 }
 
-fn load_items(mut item_db: ResMut<ItemDatabase>) {
-    // For now, we mock the database loading.
-    // In a real implementation, this would load from assets/items/*.ron
-
-    let mut items = vec![
-        ItemDefinition {
-            id: "steel_sword".to_string(),
-            name: "Steel Sword".to_string(),
-            width: 1,
-            height: 2,
-            shape: vec![], // Will be populated below
-            material: MaterialType::Steel,
-            item_type: ItemType::Weapon,
-            rarity: ItemRarity::Common,
-            price: 5,
-            tags: vec![ItemTag::Weapon],
-            synergies: vec![],
-            attack: 10.0,
-            defense: 0.0,
-            speed: 0.0,
-        },
-        ItemDefinition {
-            id: "silver_dagger".to_string(),
-            name: "Silver Dagger".to_string(),
-            width: 1,
-            height: 1,
-            shape: vec![],
-            material: MaterialType::Silver,
-            item_type: ItemType::Weapon,
-            rarity: ItemRarity::Rare,
-            price: 7,
-            tags: vec![ItemTag::Weapon],
-            synergies: vec![],
-            attack: 8.0,
-            defense: 0.0,
-            speed: 5.0,
-        },
-        ItemDefinition {
-            id: "health_potion".to_string(),
-            name: "Health Potion".to_string(),
-            width: 1,
-            height: 1,
-            shape: vec![],
-            material: MaterialType::Flesh,
-            item_type: ItemType::Consumable,
-            rarity: ItemRarity::Common,
-            price: 3,
-            tags: vec![ItemTag::Potion],
-            synergies: vec![],
-            attack: 0.0,
-            defense: 0.0,
-            speed: 0.0,
-        },
-        ItemDefinition {
-            id: "whetstone".to_string(),
-            name: "Whetstone".to_string(),
-            width: 1,
-            height: 1,
-            shape: vec![],
-            material: MaterialType::Steel,
-            item_type: ItemType::Consumable,
-            rarity: ItemRarity::Common,
-            price: 4,
-            tags: vec![ItemTag::Valuable],
-            synergies: vec![
-                SynergyDefinition {
-                    offset: IVec2::new(1, 0), // Right
-                    target_tags: vec![ItemTag::Weapon],
-                    effect: SynergyEffect::BuffTarget { stat: StatType::Attack, value: 5.0 },
-                    visual_type: SynergyVisualType::Star,
-                },
-                SynergyDefinition {
-                    offset: IVec2::new(-1, 0), // Left
-                    target_tags: vec![ItemTag::Weapon],
-                    effect: SynergyEffect::BuffTarget { stat: StatType::Attack, value: 5.0 },
-                    visual_type: SynergyVisualType::Star,
-                },
-                SynergyDefinition {
-                    offset: IVec2::new(0, 1), // Top
-                    target_tags: vec![ItemTag::Weapon],
-                    effect: SynergyEffect::BuffTarget { stat: StatType::Attack, value: 5.0 },
-                    visual_type: SynergyVisualType::Star,
-                },
-                SynergyDefinition {
-                    offset: IVec2::new(0, -1), // Bottom
-                    target_tags: vec![ItemTag::Weapon],
-                    effect: SynergyEffect::BuffTarget { stat: StatType::Attack, value: 5.0 },
-                    visual_type: SynergyVisualType::Star,
-                }
-            ],
-            attack: 0.0,
-            defense: 0.0,
-            speed: 0.0,
-        },
-        // Adding more items to test rarity
-        ItemDefinition {
-            id: "epic_shield".to_string(),
-            name: "Epic Shield".to_string(),
-            width: 2,
-            height: 2,
-            shape: vec![],
-            material: MaterialType::Steel,
-            item_type: ItemType::Weapon,
-            rarity: ItemRarity::Epic,
-            price: 12,
-            tags: vec![ItemTag::Weapon],
-            synergies: vec![],
-            attack: 2.0,
-            defense: 20.0,
-            speed: -2.0,
-        },
-        ItemDefinition {
-            id: "legendary_bow".to_string(),
-            name: "Legendary Bow".to_string(),
-            width: 1,
-            height: 3,
-            shape: vec![],
-            material: MaterialType::Flesh,
-            item_type: ItemType::Weapon,
-            rarity: ItemRarity::Legendary,
-            price: 25,
-            tags: vec![ItemTag::Weapon],
-            synergies: vec![],
-            attack: 15.0,
-            defense: 0.0,
-            speed: 10.0,
-        },
-        ItemDefinition {
-             id: "unique_charm".to_string(),
-             name: "Unique Charm".to_string(),
-             width: 1,
-             height: 1,
-             shape: vec![],
-             material: MaterialType::Silver,
-             item_type: ItemType::Consumable,
-             rarity: ItemRarity::Unique,
-             price: 50,
-             tags: vec![ItemTag::Valuable],
-             synergies: vec![],
-             attack: 0.0,
-             defense: 0.0,
-             speed: 0.0,
-        },
-        // Bags
-        ItemDefinition {
-            id: "starter_bag".to_string(),
-            name: "Starter Bag".to_string(),
-            width: 3, // Restored to reasonable default
-            height: 3,
-            shape: vec![],
-            material: MaterialType::Flesh, // Leather
-            item_type: ItemType::Bag { bag_type: BagType::Default },
-            rarity: ItemRarity::Common, // Not in shop typically
-            price: 0,
-            tags: vec![],
-            synergies: vec![],
-            attack: 0.0,
-            defense: 0.0,
-            speed: 0.0,
-        },
-        ItemDefinition {
-            id: "leather_bag".to_string(),
-            name: "Leather Bag".to_string(),
-            width: 2,
-            height: 2,
-            shape: vec![],
-            material: MaterialType::Flesh,
-            item_type: ItemType::Bag { bag_type: BagType::Leather },
-            rarity: ItemRarity::Common,
-            price: 4,
-            tags: vec![],
-            synergies: vec![],
-            attack: 0.0,
-            defense: 0.0,
-            speed: 0.0,
-        },
-        ItemDefinition {
-            id: "fanny_pack".to_string(),
-            name: "Fanny Pack".to_string(),
-            width: 2,
-            height: 1,
-            shape: vec![],
-            material: MaterialType::Flesh,
-            item_type: ItemType::Bag { bag_type: BagType::FannyPack },
-            rarity: ItemRarity::Rare,
-            price: 6,
-            tags: vec![],
-            synergies: vec![
-                 // Example synergy: Speed up items inside? For now placeholder.
-            ],
-            attack: 0.0,
-            defense: 0.0,
-            speed: 0.0,
-        },
-        ItemDefinition {
-            id: "potion_belt".to_string(),
-            name: "Potion Belt".to_string(),
-            width: 3,
-            height: 1,
-            shape: vec![],
-            material: MaterialType::Flesh,
-            item_type: ItemType::Bag { bag_type: BagType::PotionBelt },
-            rarity: ItemRarity::Epic,
-            price: 8,
-            tags: vec![],
-            synergies: vec![],
-            attack: 0.0,
-            defense: 0.0,
-            speed: 0.0,
-        },
-        ItemDefinition {
-            id: "stamina_sack".to_string(),
-            name: "Stamina Sack".to_string(),
-            width: 1,
-            height: 1,
-            shape: vec![],
-            material: MaterialType::Flesh,
-            item_type: ItemType::Bag { bag_type: BagType::StaminaSack },
-            rarity: ItemRarity::Rare,
-            price: 5,
-            tags: vec![],
-            synergies: vec![],
-            attack: 0.0,
-            defense: 0.0,
-            speed: 0.0,
-        },
-    ];
-
-    // Auto-generate rectangular shapes if empty
-    for item in items.iter_mut() {
-        if item.shape.is_empty() {
-            for y in 0..item.height {
-                for x in 0..item.width {
-                    item.shape.push(IVec2::new(x as i32, y as i32));
-                }
+pub(crate) fn load_items(mut item_db: ResMut<ItemDatabase>, mut hot_reload: ResMut<ItemHotReload>) {
+    hot_reload.last_stamp = dir_stamp(&hot_reload.dir);
+
+    match ItemDatabase::load_from_dir(&hot_reload.dir) {
+        Ok(db) => {
+            *item_db = db;
+            info!("ItemDatabase loaded with {} items and {} recipes.", item_db.items.len(), item_db.recipes.len());
+        }
+        Err(e) => error!("Failed to load item database: {}", e),
+    }
+}
+
+fn hot_reload_items(
+    time: Res<Time>,
+    mut hot_reload: ResMut<ItemHotReload>,
+    mut item_db: ResMut<ItemDatabase>,
+    mut ev_reloaded: EventWriter<ItemDatabaseReloaded>,
+) {
+    if !hot_reload.timer.tick(time.delta()).just_finished() { return; }
+
+    let stamp = dir_stamp(&hot_reload.dir);
+    if stamp == hot_reload.last_stamp { return; }
+    hot_reload.last_stamp = stamp;
+
+    // On error keep the previous database so a half-saved file doesn't wipe the game
+    match ItemDatabase::load_from_dir(&hot_reload.dir) {
+        Ok(db) => {
+            *item_db = db;
+            info!("ItemDatabase reloaded: {} items, {} recipes.", item_db.items.len(), item_db.recipes.len());
+            ev_reloaded.send(ItemDatabaseReloaded);
+        }
+        Err(e) => error!("Item reload failed, keeping previous definitions: {}", e),
+    }
+}
+
+/// Change detector: a hash of each item file's contents, so added, removed and edited files
+/// all show up regardless of how coarse or skewed modification times are.
+fn dir_stamp(dir: &Path) -> Option<HashMap<PathBuf, u64>> {
+    let mut stamp = HashMap::new();
+    for path in ron_files_in(dir).ok()? {
+        // A file that can't be read right now (e.g. mid-save) counts as changed once it can
+        let Ok(contents) = fs::read(&path) else { continue; };
+        let mut hasher = DefaultHasher::new();
+        contents.hash(&mut hasher);
+        stamp.insert(path, hasher.finish());
+    }
+    Some(stamp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+
+    #[test]
+    fn test_load_item_assets() {
+        let db = ItemDatabase::shipped();
+
+        let sword = &db.items["steel_sword"];
+        assert_eq!(sword.attack, 10.0);
+        // Shape generated from 1x2
+        assert_eq!(sword.shape, vec![IVec2::new(0, 0), IVec2::new(0, 1)]);
+
+        let belt = &db.items["potion_belt"];
+        assert_eq!(belt.item_type, ItemType::Bag { bag_type: BagType::PotionBelt });
+
+        assert_eq!(db.items["whetstone"].synergies.len(), 4);
+        assert!(!db.recipes.is_empty());
+
+        let chainmail = &db.items["chainmail"];
+        assert_eq!(chainmail.item_type, ItemType::Armor);
+        assert_eq!(chainmail.stat_lines(), vec!["Defense +6", "Speed -1", "Health +15"]);
+    }
+
+    #[test]
+    fn test_duplicate_id_names_both_files() {
+        let dir = std::env::temp_dir().join(format!("cw_items_dup_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let item = r#"(items: [(id: "rock", name: "Rock", width: 1, height: 1)])"#;
+        fs::write(dir.join("a.ron"), item).unwrap();
+        fs::write(dir.join("b.ron"), item).unwrap();
+
+        let err = ItemDatabase::load_from_dir(&dir).unwrap_err();
+        fs::remove_dir_all(&dir).unwrap();
+
+        match err {
+            ItemLoadError::DuplicateId { id, first, second } => {
+                assert_eq!(id, "rock");
+                assert!(first.ends_with("a.ron"));
+                assert!(second.ends_with("b.ron"));
             }
+            other => panic!("unexpected error: {}", other),
         }
     }
 
-    for item in items {
-        item_db.items.insert(item.id.clone(), item);
+    #[test]
+    fn test_hot_reload_picks_up_edits() {
+        let dir = std::env::temp_dir().join(format!("cw_items_reload_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("rocks.ron");
+        let rock = |attack: f32| format!(r#"(items: [(id: "rock", name: "Rock", width: 1, height: 1, attack: {:?})])"#, attack);
+        fs::write(&path, rock(1.0)).unwrap();
+
+        let mut world = World::new();
+        world.init_resource::<ItemDatabase>();
+        world.init_resource::<Time>();
+        world.init_resource::<Events<ItemDatabaseReloaded>>();
+        world.insert_resource(ItemHotReload { dir: dir.clone(), ..default() });
+        world.run_system_once(load_items).unwrap();
+        assert_eq!(world.resource::<ItemDatabase>().items["rock"].attack, 1.0);
+
+        // Same length, and likely the same modification time on coarse clocks; only the contents differ
+        let before = dir_stamp(&dir);
+        fs::write(&path, rock(5.0)).unwrap();
+        assert_ne!(dir_stamp(&dir), before);
+
+        let poll = |world: &mut World| {
+            world.resource_mut::<Time>().advance_by(std::time::Duration::from_secs_f32(1.1));
+            world.run_system_once(hot_reload_items).unwrap();
+        };
+        poll(&mut world);
+        assert_eq!(world.resource::<ItemDatabase>().items["rock"].attack, 5.0);
+        assert_eq!(world.resource::<Events<ItemDatabaseReloaded>>().len(), 1);
+
+        // Nothing changed since, so no further reload
+        poll(&mut world);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(world.resource::<Events<ItemDatabaseReloaded>>().len(), 1);
     }
 
-    info!("ItemDatabase loaded with {} items.", item_db.items.len());
-
-    // Mock Recipes
-    item_db.recipes = vec![
-        RecipeDefinition {
-            ingredients: vec!["steel_sword".to_string(), "whetstone".to_string()],
-            result: "hero_sword".to_string(), // Need to define this item if we want it to work fully
-            catalysts: vec![],
-        },
-        RecipeDefinition {
-            ingredients: vec!["health_potion".to_string(), "health_potion".to_string()],
-            result: "strong_health_potion".to_string(),
-            catalysts: vec![],
+    #[test]
+    fn test_parse_error_names_item() {
+        let dir = std::env::temp_dir().join(format!("cw_items_bad_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("bad.ron"),
+            "(items: [\n    (\n        id: \"rock\",\n        name: \"Rock\",\n        width: 1,\n        height: 1,\n    ),\n    (\n        id: \"broken\",\n        name: \"Broken\",\n        width: \"wide\",\n    ),\n])",
+        ).unwrap();
+
+        let err = ItemDatabase::load_from_dir(&dir).unwrap_err();
+        fs::remove_dir_all(&dir).unwrap();
+
+        match err {
+            ItemLoadError::Parse { item_id, .. } => assert_eq!(item_id.as_deref(), Some("broken")),
+            other => panic!("unexpected error: {}", other),
         }
-    ];
+    }
 }
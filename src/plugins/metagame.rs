@@ -8,13 +8,26 @@ pub struct SaveData {
     pub inventory: Vec<SavedItem>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct SavedItem {
     pub item_id: String,
     pub grid_x: i32,
     pub grid_y: i32,
     #[serde(default)]
     pub rotation: u8,
+    /// Mirrored left to right before the rotation is applied
+    #[serde(default)]
+    pub flipped: bool,
+    /// Sitting in the storage strip instead of on the grid
+    #[serde(default)]
+    pub in_storage: bool,
+}
+
+impl SavedItem {
+    /// An unflipped item on the grid; set the other fields with struct update syntax.
+    pub fn new(item_id: impl Into<String>, grid_x: i32, grid_y: i32, rotation: u8) -> Self {
+        Self { item_id: item_id.into(), grid_x, grid_y, rotation, ..default() }
+    }
 }
 
 #[derive(Resource, Debug, Serialize, Deserialize, Clone)]
@@ -51,8 +64,10 @@ impl Default for GlobalTime {
 
 // Plugin
 use crate::plugins::core::{GameState, DaySubState};
-use crate::plugins::inventory::{InventoryGridState, GridPosition, ItemRotation, InventoryItem, spawn_item_entity, InventoryGridContainer};
-use crate::plugins::items::{ItemDatabase, ItemType};
+use crate::plugins::inventory::{
+    rebuild_inventory_grid, snapshot_inventory, spawn_saved_items, InventoryGridContainer, InventoryGridState, InventoryItem, SnapshotQuery,
+};
+use crate::plugins::items::ItemDatabase;
 use std::fs::File;
 use std::io::{Write, Read};
 
@@ -61,7 +76,8 @@ pub struct MetagamePlugin;
 #[derive(Resource, Default, Debug)]
 pub struct PendingItems(pub Vec<String>);
 
-/// Holds inventory state between Evening phases (e.g. during Combat)
+/// Holds inventory state between Evening phases (e.g. during Combat).
+/// The evening grid is respawned from it on entry and written back on exit.
 #[derive(Resource, Debug, Clone)]
 pub struct PersistentInventory {
     pub items: Vec<SavedItem>,
@@ -72,12 +88,7 @@ impl Default for PersistentInventory {
         Self {
             items: vec![
                 // Starter Bag at center-ish
-                SavedItem {
-                    item_id: "starter_bag".to_string(),
-                    grid_x: 2,
-                    grid_y: 2,
-                    rotation: 0,
-                }
+                SavedItem::new("starter_bag", 2, 2, 0)
             ],
         }
     }
@@ -103,7 +114,11 @@ impl Plugin for MetagamePlugin {
     }
 }
 
-fn spawn_city_ui(mut commands: Commands) {
+fn spawn_city_ui(
+    mut commands: Commands,
+    outcome: Option<Res<crate::plugins::outcome::CombatOutcome>>,
+    item_db: Res<ItemDatabase>,
+) {
     commands.spawn((
         Node {
             width: Val::Percent(100.0),
@@ -126,6 +141,14 @@ fn spawn_city_ui(mut commands: Commands) {
             Node { margin: UiRect::bottom(Val::Px(20.0)), ..default() },
         ));
 
+        if let Some(outcome) = &outcome {
+            parent.spawn((
+                Text::new(format!("Last night: {}", outcome.summary(&item_db))),
+                TextFont { font_size: 18.0, ..default() },
+                TextColor(Color::srgb(0.8, 0.8, 0.6)),
+            ));
+        }
+
         let buttons = [
             ("Visit Market (Sword)", "steel_sword"),
             ("Visit Slums (Dagger)", "silver_dagger"),
@@ -227,22 +250,12 @@ fn day_start_logic() {
 pub fn create_save_data(
     player_stats: &PlayerStats,
     global_time: &GlobalTime,
-    q_items: &Query<(&InventoryItem, &GridPosition, &ItemRotation)>,
+    inventory: &PersistentInventory,
 ) -> SaveData {
-    let mut saved_items = Vec::new();
-    for (item, pos, rot) in q_items.iter() {
-        saved_items.push(SavedItem {
-            item_id: item.item_id.clone(),
-            grid_x: pos.0.x,
-            grid_y: pos.0.y,
-            rotation: rot.0,
-        });
-    }
-
     SaveData {
         player_stats: player_stats.clone(),
         global_time: global_time.clone(),
-        inventory: saved_items,
+        inventory: inventory.items.clone(),
     }
 }
 
@@ -250,10 +263,19 @@ fn save_system(
     input: Res<ButtonInput<KeyCode>>,
     player_stats: Res<PlayerStats>,
     global_time: Res<GlobalTime>,
-    q_items: Query<(&InventoryItem, &GridPosition, &ItemRotation)>,
+    persistent_inventory: Res<PersistentInventory>,
+    q_items: SnapshotQuery,
+    q_container: Query<Entity, With<InventoryGridContainer>>,
 ) {
     if input.just_pressed(KeyCode::F5) {
-        let save_data = create_save_data(&player_stats, &global_time, &q_items);
+        // During the evening the grid is newer than the persistent copy
+        let save_data = match q_container.get_single() {
+            Ok(container) => {
+                let inventory = PersistentInventory { items: snapshot_inventory(&q_items, container) };
+                create_save_data(&player_stats, &global_time, &inventory)
+            }
+            Err(_) => create_save_data(&player_stats, &global_time, &persistent_inventory),
+        };
 
         match serde_json::to_string_pretty(&save_data) {
             Ok(json) => {
@@ -277,9 +299,10 @@ fn load_system_debug(
     mut commands: Commands,
     mut player_stats: ResMut<PlayerStats>,
     mut global_time: ResMut<GlobalTime>,
+    mut persistent_inventory: ResMut<PersistentInventory>,
     mut grid_state: ResMut<InventoryGridState>,
     item_db: Res<ItemDatabase>,
-    q_items: Query<Entity, With<InventoryItem>>,
+    q_items: Query<(Entity, &Parent), With<InventoryItem>>,
     q_container: Query<Entity, With<InventoryGridContainer>>,
 ) {
     if input.just_pressed(KeyCode::F9) {
@@ -291,50 +314,19 @@ fn load_system_debug(
                         // Apply loaded state
                         *player_stats = data.player_stats;
                         *global_time = data.global_time;
+                        persistent_inventory.items = data.inventory;
 
-                        // Clear current inventory
-                        for entity in q_items.iter() {
-                            commands.entity(entity).despawn_recursive();
-                        }
-
-                        // Clear grid slots manually or via rebuild?
-                        // Grid slots are derived from bags.
-                        // When we clear items (including bags), we should clear slots too.
-                        grid_state.slots.clear();
-
-                        // Respawn items
+                        // Outside the evening there is no grid; it is spawned from the persistent inventory on entry
                         if let Ok(container) = q_container.get_single() {
-                            // Pass 1: Bags first to establish grid
-                             for saved_item in &data.inventory {
-                                if let Some(def) = item_db.items.get(&saved_item.item_id) {
-                                    if matches!(def.item_type, ItemType::Bag { .. }) {
-                                        spawn_item_entity(
-                                             &mut commands,
-                                             container,
-                                             def,
-                                             IVec2::new(saved_item.grid_x, saved_item.grid_y),
-                                             saved_item.rotation,
-                                             &mut grid_state
-                                        );
-                                    }
-                                }
-                            }
-
-                            // Pass 2: Items
-                            for saved_item in &data.inventory {
-                                if let Some(def) = item_db.items.get(&saved_item.item_id) {
-                                     if !matches!(def.item_type, ItemType::Bag { .. }) {
-                                        spawn_item_entity(
-                                             &mut commands,
-                                             container,
-                                             def,
-                                             IVec2::new(saved_item.grid_x, saved_item.grid_y),
-                                             saved_item.rotation,
-                                             &mut grid_state
-                                        );
-                                     }
+                            // Clear current inventory (shop previews stay)
+                            for (entity, parent) in q_items.iter() {
+                                if parent.get() == container {
+                                    commands.entity(entity).despawn_recursive();
                                 }
                             }
+                            spawn_saved_items(&mut commands, container, &persistent_inventory.items, &item_db, &mut grid_state);
+                            // Grid slots are derived from bags, so rebuild once the old ones are gone
+                            commands.run_system_cached(rebuild_inventory_grid);
                         }
 
                         info!("Game loaded successfully.");
@@ -1,10 +1,20 @@
 pub mod core;
 pub mod inventory;
 pub mod items;
+pub mod item_validation;
 pub mod combat;
+pub mod combat_log;
+pub mod combat_fx;
+pub mod combat_sim;
+pub mod consumables;
+pub mod ammo;
+pub mod crafting;
+pub mod enemies;
 pub mod metagame;
 pub mod mutation;
+pub mod outcome;
 pub mod ui;
 pub mod shop;
+pub mod status_effects;
 pub mod visualization;
 pub mod inventory_utils;
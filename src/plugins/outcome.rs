@@ -0,0 +1,437 @@
+use bevy::prelude::*;
+use rand::Rng;
+use crate::plugins::combat::{CombatWinner, Team};
+use crate::plugins::combat_fx::DEATH_FADE_SECONDS;
+use crate::plugins::combat_log::CombatReplay;
+use crate::plugins::consumables::remove_spent_consumables;
+use crate::plugins::core::GameState;
+use crate::plugins::enemies::EnemyDefinition;
+use crate::plugins::inventory::InventoryGridState;
+use crate::plugins::items::{ItemDatabase, ItemType};
+use crate::plugins::metagame::{GlobalTime, PendingItems, PersistentInventory, PlayerStats};
+use crate::plugins::shop::ShopState;
+
+/// Thalers paid per enemy defeated.
+pub const BOUNTY_PER_ENEMY: u32 = 10;
+pub const VICTORY_REPUTATION: u32 = 5;
+pub const DEFEAT_REPUTATION: u32 = 10;
+/// Infection gained from losing a fight.
+pub const DEFEAT_INFECTION: u32 = 25;
+/// The run ends once infection reaches this.
+pub const MAX_INFECTION: u32 = 100;
+/// Hour the next day starts at after a night fight.
+pub const DAWN_HOUR: u32 = 6;
+/// How long the arena stays up after the fight is decided, so the killing blow and death fade can play.
+pub const ARENA_EXIT_SECONDS: f32 = DEATH_FADE_SECONDS;
+
+/// Applies the consequences of a decided fight (rewards, penalties, the next day)
+/// and ends the run with a GameOver screen when infection takes over.
+pub struct OutcomePlugin;
+
+impl Plugin for OutcomePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NightEncounter>()
+           .add_systems(Update, (
+                // Spent consumables go first, so a defeat can't take an item out from under their indices
+                remove_spent_consumables.run_if(resource_changed::<CombatWinner>),
+                resolve_combat_outcome.run_if(resource_changed::<CombatWinner>),
+                hand_out_outcome.run_if(resource_exists_and_changed::<CombatOutcome>),
+                leave_arena.run_if(resource_exists::<ArenaExitTimer>),
+            ).chain()
+                .run_if(in_state(GameState::NightPhase))
+                .run_if(not(resource_exists::<CombatReplay>)))
+           .add_systems(OnExit(GameState::NightPhase), clear_arena_exit_timer)
+           .add_systems(OnEnter(GameState::GameOver), spawn_game_over_ui)
+           .add_systems(OnExit(GameState::GameOver), cleanup_game_over_ui)
+           .add_systems(Update, restart_button_system.run_if(in_state(GameState::GameOver)));
+    }
+}
+
+/// Counts down from the end of a fight to leaving the arena.
+#[derive(Resource, Debug)]
+pub struct ArenaExitTimer(pub Timer);
+
+/// The enemies faced tonight, kept for bounties and loot after they die.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct NightEncounter(pub Vec<EnemyDefinition>);
+
+/// What the last fight cost or earned.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct CombatOutcome {
+    pub winner: Team,
+    pub thalers: u32,
+    /// Reputation change, negative on defeat
+    pub reputation: i32,
+    pub infection: u32,
+    /// Item ids dropped by the enemies, handed out as pending items
+    pub loot: Vec<String>,
+    /// Item ids taken from the inventory
+    pub lost_items: Vec<String>,
+    pub game_over: bool,
+}
+
+impl CombatOutcome {
+    /// One line per consequence, for the city and GameOver screens.
+    pub fn summary(&self, item_db: &ItemDatabase) -> String {
+        let name = |id: &String| item_db.items.get(id).map_or(id.clone(), |d| d.name.clone());
+        let mut lines = vec![match self.winner {
+            Team::Player => "Victory!".to_string(),
+            Team::Enemy => "Defeated...".to_string(),
+        }];
+        if self.thalers > 0 {
+            lines.push(format!("+{} thalers", self.thalers));
+        }
+        if self.reputation != 0 {
+            lines.push(format!("{:+} reputation", self.reputation));
+        }
+        if self.infection > 0 {
+            lines.push(format!("+{} infection", self.infection));
+        }
+        if !self.loot.is_empty() {
+            lines.push(format!("Loot: {}", self.loot.iter().map(name).collect::<Vec<_>>().join(", ")));
+        }
+        if !self.lost_items.is_empty() {
+            lines.push(format!("Lost: {}", self.lost_items.iter().map(name).collect::<Vec<_>>().join(", ")));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Rewards or punishes the player for a fight against `encounter` and moves time on to the next morning.
+/// Victory pays a bounty per enemy, reputation and rolled loot. Defeat costs reputation, adds
+/// infection and takes one random non-bag item the player fought with. Bags stay so nothing is
+/// left unsupported, and stored items are spared: they weren't in the fight.
+pub fn resolve_outcome(
+    winner: Team,
+    encounter: &[EnemyDefinition],
+    stats: &mut PlayerStats,
+    time: &mut GlobalTime,
+    inventory: &mut PersistentInventory,
+    item_db: &ItemDatabase,
+    rng: &mut impl Rng,
+) -> CombatOutcome {
+    let mut outcome = CombatOutcome {
+        winner,
+        thalers: 0,
+        reputation: 0,
+        infection: 0,
+        loot: Vec::new(),
+        lost_items: Vec::new(),
+        game_over: false,
+    };
+
+    match winner {
+        Team::Player => {
+            outcome.thalers = BOUNTY_PER_ENEMY * encounter.len() as u32;
+            outcome.reputation = VICTORY_REPUTATION as i32;
+            stats.thalers += outcome.thalers;
+            stats.reputation += VICTORY_REPUTATION;
+
+            for entry in encounter.iter().flat_map(|enemy| &enemy.loot) {
+                if rng.gen::<f32>() < entry.chance {
+                    outcome.loot.push(entry.item_id.clone());
+                }
+            }
+        }
+        Team::Enemy => {
+            let lost_reputation = DEFEAT_REPUTATION.min(stats.reputation);
+            outcome.reputation = -(lost_reputation as i32);
+            outcome.infection = DEFEAT_INFECTION;
+            stats.reputation -= lost_reputation;
+            stats.infection += DEFEAT_INFECTION;
+
+            let losable: Vec<usize> = inventory.items.iter().enumerate()
+                .filter(|(_, item)| {
+                    !item.in_storage
+                        && item_db.items.get(&item.item_id).is_some_and(|def| !matches!(def.item_type, ItemType::Bag { .. }))
+                })
+                .map(|(index, _)| index)
+                .collect();
+            if !losable.is_empty() {
+                let lost = inventory.items.remove(losable[rng.gen_range(0..losable.len())]);
+                outcome.lost_items.push(lost.item_id);
+            }
+        }
+    }
+
+    outcome.game_over = stats.infection >= MAX_INFECTION;
+    time.day += 1;
+    time.hour = DAWN_HOUR;
+    outcome
+}
+
+fn resolve_combat_outcome(
+    mut commands: Commands,
+    winner: Res<CombatWinner>,
+    encounter: Res<NightEncounter>,
+    mut stats: ResMut<PlayerStats>,
+    mut time: ResMut<GlobalTime>,
+    mut inventory: ResMut<PersistentInventory>,
+    item_db: Res<ItemDatabase>,
+) {
+    let Some(team) = winner.0 else { return; };
+    let outcome = resolve_outcome(team, &encounter.0, &mut stats, &mut time, &mut inventory, &item_db, &mut rand::thread_rng());
+    info!("Combat outcome: {:?}", outcome);
+    commands.insert_resource(outcome);
+    commands.insert_resource(ArenaExitTimer(Timer::from_seconds(ARENA_EXIT_SECONDS, TimerMode::Once)));
+}
+
+/// Queues the loot as found items.
+fn hand_out_outcome(
+    outcome: Res<CombatOutcome>,
+    mut pending_items: ResMut<PendingItems>,
+) {
+    pending_items.0.extend(outcome.loot.iter().cloned());
+}
+
+/// Returns to the city once the exit timer runs out, or to the GameOver screen when the fight ended the run.
+fn leave_arena(
+    time: Res<Time>,
+    mut timer: ResMut<ArenaExitTimer>,
+    outcome: Option<Res<CombatOutcome>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !timer.0.tick(time.delta()).finished() { return; }
+    if outcome.is_some_and(|o| o.game_over) {
+        info!("Infection has taken over. Game over.");
+        next_state.set(GameState::GameOver);
+    } else {
+        info!("Returning to City...");
+        next_state.set(GameState::DayPhase);
+    }
+}
+
+fn clear_arena_exit_timer(mut commands: Commands) {
+    commands.remove_resource::<ArenaExitTimer>();
+}
+
+#[derive(Component)]
+struct GameOverUiRoot;
+
+#[derive(Component)]
+struct RestartButton;
+
+fn spawn_game_over_ui(
+    mut commands: Commands,
+    outcome: Option<Res<CombatOutcome>>,
+    time: Res<GlobalTime>,
+    item_db: Res<ItemDatabase>,
+) {
+    let summary = outcome.map_or(String::new(), |o| o.summary(&item_db));
+
+    commands.spawn((
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            display: Display::Flex,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(20.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.1, 0.0, 0.0)),
+        GameOverUiRoot,
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            Text::new("The Curse Takes Hold"),
+            TextFont { font_size: 40.0, ..default() },
+            TextColor(Color::srgb(0.8, 0.1, 0.1)),
+        ));
+        parent.spawn((
+            Text::new(format!("You lasted until day {}\n{}", time.day.saturating_sub(1), summary)),
+            TextFont { font_size: 20.0, ..default() },
+            TextColor(Color::WHITE),
+        ));
+
+        parent.spawn((
+            Button,
+            Node {
+                width: Val::Px(200.0),
+                height: Val::Px(50.0),
+                border: UiRect::all(Val::Px(2.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BorderColor(Color::BLACK),
+            BackgroundColor(Color::srgb(0.3, 0.3, 0.4)),
+            RestartButton,
+        ))
+        .with_children(|p| {
+            p.spawn((
+                Text::new("Restart"),
+                TextFont { font_size: 20.0, ..default() },
+                TextColor(Color::WHITE),
+            ));
+        });
+    });
+}
+
+fn cleanup_game_over_ui(mut commands: Commands, q_root: Query<Entity, With<GameOverUiRoot>>) {
+    for e in q_root.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+}
+
+/// Starts a fresh run: everything the previous run accumulated goes back to its defaults.
+fn restart_button_system(
+    mut commands: Commands,
+    q_buttons: Query<&Interaction, (Changed<Interaction>, With<RestartButton>)>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for interaction in q_buttons.iter() {
+        if *interaction == Interaction::Pressed {
+            info!("Restarting run");
+            commands.insert_resource(PlayerStats::default());
+            commands.insert_resource(GlobalTime::default());
+            commands.insert_resource(PersistentInventory::default());
+            commands.insert_resource(PendingItems::default());
+            commands.insert_resource(ShopState::default());
+            commands.insert_resource(InventoryGridState::default());
+            commands.insert_resource(NightEncounter::default());
+            commands.remove_resource::<CombatOutcome>();
+            next_state.set(GameState::DayPhase);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::combat::UnitType;
+    use crate::plugins::consumables::Consumable;
+    use crate::plugins::enemies::LootEntry;
+    use crate::plugins::items::{BagType, ConsumableTrigger, ItemDefinition};
+    use crate::plugins::metagame::SavedItem;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn ghoul() -> EnemyDefinition {
+        EnemyDefinition {
+            name: "Ghoul".to_string(),
+            attack: 1.0,
+            loot: vec![LootEntry { item_id: "sword".to_string(), chance: 1.0 }],
+            ..EnemyDefinition::test("ghoul", UnitType::Monster, 10.0)
+        }
+    }
+
+    #[test]
+    fn test_outcome_rewards_and_penalties() {
+        let mut db = ItemDatabase::default();
+        let bag = ItemDefinition { id: "bag".to_string(), item_type: ItemType::Bag { bag_type: BagType::Default }, ..default() };
+        let sword = ItemDefinition { id: "sword".to_string(), ..default() };
+        db.items.insert(bag.id.clone(), bag);
+        db.items.insert(sword.id.clone(), sword);
+
+        let mut stats = PlayerStats::default();
+        let mut time = GlobalTime::default();
+        let mut inventory = PersistentInventory { items: vec![SavedItem::new("bag", 0, 0, 0), SavedItem::new("sword", 0, 0, 0)] };
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let won = resolve_outcome(Team::Player, &[ghoul(), ghoul()], &mut stats, &mut time, &mut inventory, &db, &mut rng);
+        assert_eq!(won.thalers, 2 * BOUNTY_PER_ENEMY);
+        assert_eq!(won.loot, vec!["sword", "sword"]);
+        assert_eq!(stats.thalers, PlayerStats::default().thalers + 2 * BOUNTY_PER_ENEMY);
+        assert_eq!((time.day, time.hour), (2, DAWN_HOUR));
+
+        // Only the sword can be lost; the bag stays
+        let lost = resolve_outcome(Team::Enemy, &[ghoul()], &mut stats, &mut time, &mut inventory, &db, &mut rng);
+        assert_eq!(lost.lost_items, vec!["sword"]);
+        assert_eq!(inventory.items.len(), 1);
+        assert_eq!(stats.infection, DEFEAT_INFECTION);
+        assert!(!lost.game_over);
+
+        stats.infection = MAX_INFECTION - DEFEAT_INFECTION;
+        let last = resolve_outcome(Team::Enemy, &[ghoul()], &mut stats, &mut time, &mut inventory, &db, &mut rng);
+        assert!(last.lost_items.is_empty());
+        assert!(last.game_over);
+    }
+
+    #[test]
+    fn test_defeat_only_takes_carried_items() {
+        let mut db = ItemDatabase::default();
+        let sword = ItemDefinition { id: "sword".to_string(), ..default() };
+        db.items.insert(sword.id.clone(), sword);
+
+        let stored = SavedItem { in_storage: true, ..SavedItem::new("sword", 0, 0, 0) };
+        let mut stats = PlayerStats::default();
+        let mut time = GlobalTime::default();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        // Nothing was carried, so nothing is lost
+        let mut inventory = PersistentInventory { items: vec![stored.clone(), stored.clone()] };
+        let lost = resolve_outcome(Team::Enemy, &[ghoul()], &mut stats, &mut time, &mut inventory, &db, &mut rng);
+        assert!(lost.lost_items.is_empty());
+        assert_eq!(inventory.items.len(), 2);
+
+        // With one carried sword among stored ones, the carried one always goes
+        for seed in 0..8 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut inventory = PersistentInventory { items: vec![stored.clone(), SavedItem::new("sword", 0, 0, 0), stored.clone()] };
+            resolve_outcome(Team::Enemy, &[ghoul()], &mut stats, &mut time, &mut inventory, &db, &mut rng);
+            assert!(inventory.items.iter().all(|item| item.in_storage));
+            assert_eq!(inventory.items.len(), 2);
+        }
+    }
+
+    #[test]
+    fn test_arena_stays_up_for_the_death_fade() {
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        world.init_resource::<NextState<GameState>>();
+        world.insert_resource(ArenaExitTimer(Timer::from_seconds(ARENA_EXIT_SECONDS, TimerMode::Once)));
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(leave_arena);
+        let mut step = |world: &mut World, seconds: f32| {
+            world.resource_mut::<Time>().advance_by(std::time::Duration::from_secs_f32(seconds));
+            schedule.run(world);
+        };
+
+        step(&mut world, ARENA_EXIT_SECONDS / 2.0);
+        assert!(matches!(*world.resource::<NextState<GameState>>(), NextState::Unchanged));
+        step(&mut world, ARENA_EXIT_SECONDS);
+        assert!(matches!(*world.resource::<NextState<GameState>>(), NextState::Pending(GameState::DayPhase)));
+    }
+
+    #[test]
+    fn test_defeat_after_drinking_a_potion() {
+        let mut db = ItemDatabase::default();
+        for def in [
+            ItemDefinition { id: "bag".to_string(), item_type: ItemType::Bag { bag_type: BagType::Default }, ..default() },
+            ItemDefinition { id: "sword".to_string(), ..default() },
+            ItemDefinition { id: "potion".to_string(), item_type: ItemType::Consumable, ..default() },
+        ] {
+            db.items.insert(def.id.clone(), def);
+        }
+
+        let mut world = World::new();
+        world.insert_resource(db);
+        world.insert_resource(CombatWinner(Some(Team::Enemy)));
+        world.insert_resource(NightEncounter(vec![ghoul()]));
+        world.init_resource::<PlayerStats>();
+        world.init_resource::<GlobalTime>();
+        world.insert_resource(PersistentInventory { items: vec![SavedItem::new("bag", 0, 0, 0), SavedItem::new("sword", 0, 0, 0), SavedItem::new("potion", 0, 0, 0)] });
+        // The potion at index 2 was drunk during the fight
+        world.spawn(Consumable {
+            item_id: "potion".to_string(),
+            inventory_index: 2,
+            trigger: ConsumableTrigger::FightStart,
+            effects: Vec::new(),
+            uses_left: 0,
+            potency: 1.0,
+            elapsed: 0,
+        });
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems((remove_spent_consumables, resolve_combat_outcome).chain());
+        schedule.run(&mut world);
+
+        // The potion is used up and the sword is the only thing left to lose
+        let items: Vec<&str> = world.resource::<PersistentInventory>().items.iter().map(|s| s.item_id.as_str()).collect();
+        assert_eq!(items, vec!["bag"]);
+        assert_eq!(world.resource::<CombatOutcome>().lost_items, vec!["sword"]);
+    }
+}
@@ -2,11 +2,17 @@ use bevy::prelude::*;
 use rand::Rng;
 use crate::plugins::items::{ItemDatabase, ItemDefinition, ItemRarity};
 use crate::plugins::metagame::{PlayerStats, GlobalTime};
-use crate::plugins::inventory::{InventoryGridState, spawn_item_entity, InventoryGridContainer, InventoryItem, GridPosition, ItemRotation};
+use crate::plugins::inventory::{
+    rebuild_inventory_grid, shape_bounds, spawn_item_entity, store_new_item, InventoryGridContainer, InventoryGridState,
+    InventoryItem,
+};
 use crate::plugins::core::GameState;
 
 pub struct ShopPlugin;
 
+/// Cell size in the shop's item previews (half a grid cell).
+const PREVIEW_CELL: f32 = 32.0;
+
 impl Plugin for ShopPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<ShopState>()
@@ -16,6 +22,7 @@ impl Plugin for ShopPlugin {
                reroll_button_system,
                buy_item_system,
                lock_item_system,
+               shop_tooltip_system,
                update_shop_ui_system
            ).run_if(in_state(GameState::EveningPhase)));
     }
@@ -46,6 +53,10 @@ struct RerollButton;
 #[derive(Component)]
 struct ShopSlot(#[allow(dead_code)] usize);
 
+/// Stats shown while the slot is hovered.
+#[derive(Component)]
+struct ShopTooltip;
+
 #[derive(Component)]
 struct LockButton(usize);
 
@@ -243,7 +254,30 @@ fn spawn_shop_ui(
                     BackgroundColor(bg_color),
                     BorderColor(if item.is_discounted { Color::srgb(1.0, 0.8, 0.0) } else { Color::BLACK }),
                     ShopSlot(i),
+                    Interaction::default(),
                 )).with_children(|slot| {
+                    // Tooltip below the slot
+                    let stats = def.stat_lines();
+                    if !stats.is_empty() {
+                        slot.spawn((
+                            Node {
+                                position_type: PositionType::Absolute,
+                                top: Val::Percent(100.0),
+                                width: Val::Px(160.0),
+                                padding: UiRect::all(Val::Px(4.0)),
+                                ..default()
+                            },
+                            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.85)),
+                            ZIndex(10),
+                            Visibility::Hidden,
+                            ShopTooltip,
+                        )).with_child((
+                            Text::new(stats.join("\n")),
+                            TextFont { font_size: 12.0, ..default() },
+                            TextColor(Color::WHITE),
+                        ));
+                    }
+
                     // Item Name
                     slot.spawn((
                         Text::new(&def.name),
@@ -260,26 +294,40 @@ fn spawn_shop_ui(
                         overflow: Overflow::clip(), // Clip if too big
                         ..default()
                     }).with_children(|preview| {
-                         let w = def.width as f32 * 32.0; // Half size for preview?
-                         let h = def.height as f32 * 32.0;
+                         // Half-size cells, drawn one by one so odd shapes show as they are
+                         let bounds = shape_bounds(&def.shape, 0);
+                         let extent = (bounds.size() + IVec2::ONE).as_vec2() * PREVIEW_CELL;
 
                          preview.spawn((
                                Node {
-                                   width: Val::Px(w),
-                                   height: Val::Px(h),
+                                   width: Val::Px(extent.x),
+                                   height: Val::Px(extent.y),
                                    ..default()
                                },
-                               BackgroundColor(Color::srgb(0.5, 0.5, 0.5)),
                                InventoryItem {
                                    item_id: def.id.clone(),
                                    base_shape: def.shape.clone(), // Updated field name
                                    width: def.width,
                                    height: def.height,
                                },
-                               GridPosition(IVec2::ZERO),
-                               ItemRotation(0),
                                PickingBehavior::IGNORE,
-                         ));
+                         )).with_children(|shape| {
+                             for offset in &def.shape {
+                                 let local = (*offset - bounds.min).as_vec2() * PREVIEW_CELL;
+                                 shape.spawn((
+                                     Node {
+                                         position_type: PositionType::Absolute,
+                                         left: Val::Px(local.x),
+                                         top: Val::Px(local.y),
+                                         width: Val::Px(PREVIEW_CELL - 1.0),
+                                         height: Val::Px(PREVIEW_CELL - 1.0),
+                                         ..default()
+                                     },
+                                     BackgroundColor(Color::srgb(0.5, 0.5, 0.5)),
+                                     PickingBehavior::IGNORE,
+                                 ));
+                             }
+                         });
                     });
 
 
@@ -458,82 +506,23 @@ fn buy_item_system(
                 let item = &mut shop_state.items[index];
                 if !item.is_sold && player_stats.thalers >= item.price {
                      if let Some(def) = item_db.items.get(&item.item_id) {
-                         // Use find_free_spot from InventoryGridState
-                         if let Some(pos) = grid_state.find_free_spot(&def.shape, def.width, def.height, None) {
-                             player_stats.thalers -= item.price;
-                             item.is_sold = true;
-
-                             if let Ok(container) = q_container.get_single() {
-                                 // We need mutable grid_state to update it?
-                                 // spawn_item_entity doesn't update grid state itself usually,
-                                 // it just spawns entities. The grid system (rebuild) will pick it up next frame?
-                                 // Wait, spawn_item_entity took &mut GridState in previous code?
-                                 // Looking at inventory.rs: spawn_item_entity takes `_grid_state: &mut InventoryGridState`.
-                                 // And it doesn't use it except for signature match?
-                                 // Wait, I should check inventory.rs again.
-                                 // It does NOT use grid_state. It just spawns Entity with components.
-                                 // The InventoryGridState::rebuild is called by `on_drag_end` or explicitly.
-                                 // If we spawn item, we should probably trigger rebuild or rely on systems.
-                                 // InventoryGridState rebuild depends on Queries.
-                                 // So just spawning entity is enough, next frame it will be in query.
-
-                                 // However, I need to pass a Mutable reference to match the function signature I created.
-                                 // So `grid_state` needs to be `ResMut`.
-                                 // But I used `grid_state.find_free_spot` which takes `&self`.
-                                 // So `ResMut` works fine as it derefs to `&mut T` which can be used as `&T`.
-
-                                 // Ah, the issue is borrowing.
-                                 // `grid_state.find_free_spot` borrows grid_state immutably.
-                                 // `spawn_item_entity` borrows it mutably.
-                                 // I cannot do both in same scope if I hold the reference?
-                                 // Actually `pos` is Copy (IVec2). So the borrow ends after `find_free_spot`.
-
-                                 // But wait, `find_free_spot` implementation calls `can_place_item`.
-
-                                 // Let's ensure `spawn_item_entity` signature is what I think it is.
-                                 // inventory.rs:
-                                 // pub fn spawn_item_entity(..., _grid_state: &mut InventoryGridState, ...)
-
-                                 // So I need ResMut.
-                                 // I'll fix the code below to use ResMut and ensure no borrow conflict.
-
-                                 // Wait, `spawn_item_entity` is called inside the `if let Some(pos)` block.
-
-                                 /*
-                                 if let Some(pos) = grid_state.find_free_spot(...) { // borrow starts and ends here?
-                                     // ...
-                                     spawn_item_entity(..., &mut grid_state); // borrow mutably here
-                                 }
-                                 */
-                                 // This is fine in Rust if the return value of find_free_spot doesn't borrow from self.
-                                 // IVec2 does not borrow.
-
-                                 // One catch: `spawn_item_entity` uses `_grid_state`. The `_` implies unused.
-                                 // I can just pass `&mut grid_state` (if I have ResMut).
-
-                                 // I will assume I need ResMut in the system signature.
-
-                                 // No, I can use `grid_state` if I have `ResMut`.
-
-                                 // Wait, I changed the system signature to Res instead of ResMut in the comment above.
-                                 // I will change it back to ResMut in the actual write.
-
-                                 spawn_item_entity(
-                                     &mut commands,
-                                     container,
-                                     def,
-                                     pos,
-                                     0,
-                                     &mut *grid_state // Deref mut
-                                 );
-                             }
-
-                            if let Ok(root) = q_root.get_single() {
-                                commands.entity(root).despawn_recursive();
-                                spawn_shop_ui(&mut commands, &shop_state, &item_db);
-                            }
-                         } else {
+                         let Ok(container) = q_container.get_single() else { continue; };
+                         // Onto the grid if there is room, otherwise parked in storage
+                         let bought = match grid_state.find_free_spot(&def.shape, def.width, def.height, None) {
+                             Some(pos) => Some(spawn_item_entity(&mut commands, container, def, pos, 0, &mut grid_state)),
+                             None => store_new_item(&mut commands, container, def, &mut grid_state),
+                         };
+                         if bought.is_none() {
                              info!("No space for item!");
+                             continue;
+                         }
+                         player_stats.thalers -= item.price;
+                         item.is_sold = true;
+                         commands.run_system_cached(rebuild_inventory_grid);
+
+                         if let Ok(root) = q_root.get_single() {
+                             commands.entity(root).despawn_recursive();
+                             spawn_shop_ui(&mut commands, &shop_state, &item_db);
                          }
                      }
                 }
@@ -542,5 +531,24 @@ fn buy_item_system(
     }
 }
 
+type HoveredShopSlotQuery<'w, 's> = Query<
+    'w, 's,
+    (&'static Interaction, &'static Children),
+    (Changed<Interaction>, With<ShopSlot>),
+>;
+
+fn shop_tooltip_system(
+    q_slots: HoveredShopSlotQuery,
+    mut q_tooltips: Query<&mut Visibility, With<ShopTooltip>>,
+) {
+    for (interaction, children) in q_slots.iter() {
+        for &child in children.iter() {
+            if let Ok(mut visibility) = q_tooltips.get_mut(child) {
+                *visibility = if *interaction == Interaction::None { Visibility::Hidden } else { Visibility::Inherited };
+            }
+        }
+    }
+}
+
 fn update_shop_ui_system() {
 }
@@ -0,0 +1,297 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::plugins::combat::{CombatEvent, CombatWinner, Health};
+use crate::plugins::items::StatType;
+
+/// Damage-over-time statuses hurt once every this many ticks rather than every tick.
+pub const STATUS_PULSE_TICKS: u32 = 20;
+/// Bleed damage per stack per pulse.
+pub const BLEED_DAMAGE_PER_STACK: f32 = 1.0;
+/// Poison damage per stack per pulse. Poison loses a stack after every pulse.
+pub const POISON_DAMAGE_PER_STACK: f32 = 2.0;
+/// Health regained per stack of Regeneration per pulse.
+pub const REGENERATION_PER_STACK: f32 = 1.0;
+/// Meter gain lost per stack of Slow.
+pub const SLOW_PER_STACK: f32 = 0.1;
+/// Outgoing damage lost per stack of Weaken.
+pub const WEAKEN_PER_STACK: f32 = 0.1;
+/// Slow and Weaken never take away more than this fraction.
+pub const MAX_REDUCTION: f32 = 0.9;
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StatusKind {
+    /// Loses health every `STATUS_PULSE_TICKS`
+    Bleed,
+    /// Loses more health than Bleed every `STATUS_PULSE_TICKS`, but wears off a stack per pulse
+    Poison,
+    /// Action meters (the unit's and its weapons') stop filling
+    Stun,
+    /// Regains health every `STATUS_PULSE_TICKS`
+    Regeneration,
+    /// Action meters (the unit's and its weapons') fill slower
+    Slow,
+    /// Deals less damage
+    Weaken,
+}
+
+/// A status on a unit. Reapplying the same kind adds stacks and refreshes the duration.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq)]
+pub struct ActiveStatus {
+    pub kind: StatusKind,
+    pub stacks: u32,
+    pub remaining_ticks: u32,
+    /// Ticks since the status was first applied; pulses are counted from here
+    pub elapsed_ticks: u32,
+}
+
+impl ActiveStatus {
+    /// Whether this tick is one of the status's pulses.
+    pub fn pulses(&self) -> bool {
+        self.elapsed_ticks > 0 && self.elapsed_ticks.is_multiple_of(STATUS_PULSE_TICKS)
+    }
+}
+
+/// Temporary flat stat bonus, e.g. from a consumable.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ActiveBuff {
+    pub stat: StatType,
+    pub value: f32,
+    pub remaining_ticks: u32,
+}
+
+/// Statuses and buffs currently on a combat unit.
+#[derive(Component, Reflect, Default, Debug, Clone)]
+#[reflect(Component)]
+pub struct StatusEffects {
+    pub active: Vec<ActiveStatus>,
+    #[reflect(ignore)]
+    pub buffs: Vec<ActiveBuff>,
+}
+
+impl StatusEffects {
+    pub fn apply(&mut self, kind: StatusKind, stacks: u32, duration: u32) {
+        match self.active.iter_mut().find(|s| s.kind == kind) {
+            Some(status) => {
+                status.stacks += stacks;
+                status.remaining_ticks = status.remaining_ticks.max(duration);
+            }
+            None => self.active.push(ActiveStatus { kind, stacks, remaining_ticks: duration, elapsed_ticks: 0 }),
+        }
+    }
+
+    pub fn add_buff(&mut self, stat: StatType, value: f32, duration: u32) {
+        self.buffs.push(ActiveBuff { stat, value, remaining_ticks: duration });
+    }
+
+    /// Sum of active buffs to `stat`.
+    pub fn buff(&self, stat: StatType) -> f32 {
+        self.buffs.iter().filter(|b| b.stat == stat).map(|b| b.value).sum()
+    }
+
+    /// Drops every status. Buffs stay.
+    pub fn cleanse(&mut self) {
+        self.active.clear();
+    }
+
+    pub fn stacks(&self, kind: StatusKind) -> u32 {
+        self.active.iter().filter(|s| s.kind == kind).map(|s| s.stacks).sum()
+    }
+
+    /// Multiplier on meter gain. Stunned units gain nothing.
+    pub fn speed_factor(&self) -> f32 {
+        if self.stacks(StatusKind::Stun) > 0 { return 0.0; }
+        1.0 - (self.stacks(StatusKind::Slow) as f32 * SLOW_PER_STACK).min(MAX_REDUCTION)
+    }
+
+    /// Multiplier on outgoing damage.
+    pub fn damage_factor(&self) -> f32 {
+        1.0 - (self.stacks(StatusKind::Weaken) as f32 * WEAKEN_PER_STACK).min(MAX_REDUCTION)
+    }
+}
+
+/// Statuses an attacker may inflict on whatever it hits.
+#[derive(Component, Default, Debug, Clone)]
+pub struct OnHit(pub Vec<crate::plugins::items::StatusApplication>);
+
+/// Per-tick part of statuses: durations count down, bleed and poison hurt and regeneration heals
+/// every `STATUS_PULSE_TICKS` since the status was applied, and expired statuses and buffs drop off.
+/// Runs between `tick_timer_system` and `combat_turn_system`.
+pub fn status_tick_system(
+    mut q_units: Query<(Entity, &mut StatusEffects, &mut Health)>,
+    winner: Res<CombatWinner>,
+    mut ev_combat: EventWriter<CombatEvent>,
+) {
+    if winner.0.is_some() { return; }
+
+    for (entity, mut statuses, mut health) in q_units.iter_mut() {
+        if health.current <= 0.0 || (statuses.active.is_empty() && statuses.buffs.is_empty()) { continue; }
+
+        for status in statuses.active.iter_mut() {
+            status.remaining_ticks = status.remaining_ticks.saturating_sub(1);
+            status.elapsed_ticks += 1;
+        }
+        for buff in statuses.buffs.iter_mut() {
+            buff.remaining_ticks = buff.remaining_ticks.saturating_sub(1);
+        }
+        statuses.buffs.retain(|b| b.remaining_ticks > 0);
+
+        let mut damage = Vec::new();
+        let mut regeneration = 0.0;
+        for status in statuses.active.iter_mut().filter(|s| s.pulses()) {
+            match status.kind {
+                StatusKind::Bleed => damage.push((StatusKind::Bleed, status.stacks as f32 * BLEED_DAMAGE_PER_STACK)),
+                StatusKind::Poison => {
+                    damage.push((StatusKind::Poison, status.stacks as f32 * POISON_DAMAGE_PER_STACK));
+                    status.stacks -= 1;
+                }
+                StatusKind::Regeneration => regeneration += status.stacks as f32 * REGENERATION_PER_STACK,
+                StatusKind::Stun | StatusKind::Slow | StatusKind::Weaken => {}
+            }
+        }
+        statuses.active.retain(|s| s.remaining_ticks > 0 && s.stacks > 0);
+
+        for (kind, amount) in damage {
+            health.current -= amount;
+            ev_combat.send(CombatEvent::StatusDamage {
+                unit: entity,
+                kind,
+                damage: amount,
+                remaining_health: health.current,
+            });
+            if health.current <= 0.0 {
+                info!("Unit {:?} succumbed to {:?}!", entity, kind);
+                ev_combat.send(CombatEvent::Death { unit: entity });
+                break;
+            }
+        }
+
+        if regeneration > 0.0 && health.current > 0.0 && health.current < health.max {
+            let before = health.current;
+            health.current = (health.current + regeneration).min(health.max);
+            ev_combat.send(CombatEvent::Healed {
+                unit: entity,
+                amount: health.current - before,
+                remaining_health: health.current,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::combat::{tick_timer_system, ActionMeter, Speed};
+
+    fn tick_world() -> World {
+        let mut world = World::new();
+        world.init_resource::<CombatWinner>();
+        world.init_resource::<Events<CombatEvent>>();
+        world
+    }
+
+    fn run_ticks(world: &mut World, ticks: u32) {
+        let mut schedule = Schedule::default();
+        schedule.add_systems(status_tick_system);
+        for _ in 0..ticks {
+            schedule.run(world);
+        }
+    }
+
+    #[test]
+    fn test_statuses_stack_and_expire() {
+        let mut world = World::new();
+        world.init_resource::<CombatWinner>();
+        world.init_resource::<Events<CombatEvent>>();
+
+        let mut statuses = StatusEffects::default();
+        statuses.apply(StatusKind::Bleed, 2, 2 * STATUS_PULSE_TICKS);
+        statuses.apply(StatusKind::Bleed, 2, 1);
+        statuses.apply(StatusKind::Slow, 20, 1);
+        assert_eq!(statuses.stacks(StatusKind::Bleed), 4);
+        assert_eq!(statuses.speed_factor(), 1.0 - MAX_REDUCTION);
+        assert_eq!(statuses.damage_factor(), 1.0);
+
+        let unit = world.spawn((statuses, Health { current: 10.0, max: 10.0 })).id();
+        let mut schedule = Schedule::default();
+        schedule.add_systems(status_tick_system);
+        schedule.run(&mut world);
+        // Slow lasted a single tick
+        assert_eq!(world.get::<StatusEffects>(unit).unwrap().active.len(), 1);
+        for _ in 0..3 * STATUS_PULSE_TICKS {
+            schedule.run(&mut world);
+        }
+
+        // The longer duration won, so bleed pulsed twice with all 4 stacks
+        let health = world.get::<Health>(unit).unwrap().current;
+        assert_eq!(health, 10.0 - 2.0 * 4.0 * BLEED_DAMAGE_PER_STACK);
+        assert!(world.get::<StatusEffects>(unit).unwrap().active.is_empty());
+    }
+
+    #[test]
+    fn test_reapplying_keeps_the_pulse() {
+        let mut world = tick_world();
+        let mut statuses = StatusEffects::default();
+        statuses.apply(StatusKind::Bleed, 1, 3 * STATUS_PULSE_TICKS);
+        let unit = world.spawn((statuses, Health { current: 10.0, max: 10.0 })).id();
+
+        run_ticks(&mut world, STATUS_PULSE_TICKS / 2);
+        // A refresh mid-pulse must not push the next pulse back
+        world.get_mut::<StatusEffects>(unit).unwrap().apply(StatusKind::Bleed, 1, 3 * STATUS_PULSE_TICKS);
+        run_ticks(&mut world, STATUS_PULSE_TICKS / 2);
+
+        assert_eq!(world.get::<Health>(unit).unwrap().current, 10.0 - 2.0 * BLEED_DAMAGE_PER_STACK);
+    }
+
+    #[test]
+    fn test_poison_hurts_and_wears_off() {
+        let mut world = tick_world();
+        let mut statuses = StatusEffects::default();
+        statuses.apply(StatusKind::Poison, 2, 10 * STATUS_PULSE_TICKS);
+        let unit = world.spawn((statuses, Health { current: 20.0, max: 20.0 })).id();
+
+        run_ticks(&mut world, STATUS_PULSE_TICKS);
+        assert_eq!(world.get::<Health>(unit).unwrap().current, 20.0 - 2.0 * POISON_DAMAGE_PER_STACK);
+        assert_eq!(world.get::<StatusEffects>(unit).unwrap().stacks(StatusKind::Poison), 1);
+
+        // The last stack hurts once more, then the poison is gone long before its duration ends
+        run_ticks(&mut world, 2 * STATUS_PULSE_TICKS);
+        assert_eq!(world.get::<Health>(unit).unwrap().current, 20.0 - 3.0 * POISON_DAMAGE_PER_STACK);
+        assert!(world.get::<StatusEffects>(unit).unwrap().active.is_empty());
+    }
+
+    #[test]
+    fn test_stun_freezes_meters() {
+        let mut world = tick_world();
+        let mut statuses = StatusEffects::default();
+        statuses.apply(StatusKind::Stun, 1, 2);
+        let unit = world.spawn((
+            statuses,
+            Health { current: 10.0, max: 10.0 },
+            Speed { value: 50.0 },
+            ActionMeter { value: 0.0, threshold: 1000.0 },
+        )).id();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems((tick_timer_system, status_tick_system).chain());
+        schedule.run(&mut world);
+        schedule.run(&mut world);
+        assert_eq!(world.get::<ActionMeter>(unit).unwrap().value, 0.0);
+
+        // Stun ran out, the meter fills again
+        schedule.run(&mut world);
+        assert_eq!(world.get::<ActionMeter>(unit).unwrap().value, 50.0);
+    }
+
+    #[test]
+    fn test_regeneration_heals_up_to_max() {
+        let mut world = tick_world();
+        let mut statuses = StatusEffects::default();
+        statuses.apply(StatusKind::Regeneration, 3, 2 * STATUS_PULSE_TICKS);
+        let unit = world.spawn((statuses, Health { current: 5.0, max: 10.0 })).id();
+
+        run_ticks(&mut world, STATUS_PULSE_TICKS);
+        assert_eq!(world.get::<Health>(unit).unwrap().current, 5.0 + 3.0 * REGENERATION_PER_STACK);
+        run_ticks(&mut world, STATUS_PULSE_TICKS);
+        assert_eq!(world.get::<Health>(unit).unwrap().current, 10.0);
+    }
+}
@@ -112,7 +112,7 @@ fn update_hud(
        *text = Text::new(format!("Day {} {:02}:00", time.day, time.hour));
    }
    for mut text in q_stats.iter_mut() {
-       *text = Text::new(format!("Thalers: {} | Rep: {}", player_stats.thalers, player_stats.reputation));
+       *text = Text::new(format!("Thalers: {} | Rep: {} | Infection: {}", player_stats.thalers, player_stats.reputation, player_stats.infection));
    }
 
    // Combat button logic
@@ -1,13 +1,17 @@
 use bevy::prelude::*;
-use crate::plugins::inventory::{InventoryGridState, GridPosition, ItemRotation, InventoryItem, rotate_shape};
-use crate::plugins::items::{ItemDatabase, ItemDefinition};
+use crate::plugins::inventory::{InventoryGridState, GridPosition, InStorage, ItemFlip, ItemRotation, InventoryItem, orient_shape};
+use crate::plugins::items::ItemDatabase;
 use crate::plugins::core::GameState;
+use crate::plugins::crafting::{update_ready_recipes, ReadyRecipes};
 
 pub struct VisualizationPlugin;
 
 impl Plugin for VisualizationPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, (draw_synergy_lines, draw_recipe_lines).run_if(in_state(GameState::EveningPhase)));
+        app.add_systems(Update, (
+            draw_synergy_lines,
+            draw_recipe_lines.after(update_ready_recipes),
+        ).run_if(in_state(GameState::EveningPhase)));
     }
 }
 
@@ -15,17 +19,25 @@ impl Plugin for VisualizationPlugin {
 // Visualization Systems
 // -------------------------------------------------------------------------------------------------
 
+type SynergySourceQuery<'w, 's> = Query<
+    'w, 's,
+    (Entity, &'static GridPosition, &'static ItemRotation, &'static ItemFlip, &'static InventoryItem),
+    Without<InStorage>,
+>;
+
 /// Draws lines between items that have active synergies.
 /// Green lines for synergies.
 fn draw_synergy_lines(
     mut gizmos: Gizmos,
-    q_items: Query<(Entity, &GridPosition, &ItemRotation, &ItemDefinition)>,
+    q_items: SynergySourceQuery,
     grid_state: Res<InventoryGridState>,
-    q_tags: Query<&ItemDefinition>,
+    item_db: Res<ItemDatabase>,
+    q_targets: Query<&InventoryItem>,
     q_transforms: Query<&GlobalTransform>,
 ) {
     // Iterate items to find active synergies
-    for (entity, pos, rot, def) in q_items.iter() {
+    for (entity, pos, rot, flip, item) in q_items.iter() {
+        let Some(def) = item_db.items.get(&item.item_id) else { continue; };
         if def.synergies.is_empty() { continue; }
 
         let start_node_transform = if let Ok(t) = q_transforms.get(entity) {
@@ -37,17 +49,16 @@ fn draw_synergy_lines(
         let start_pos = start_node_transform.translation().truncate();
 
         for synergy in &def.synergies {
-            // Calculate target grid position
-            let rotated_offset_vec = rotate_shape(&vec![synergy.offset], rot.0);
-            if rotated_offset_vec.is_empty() { continue; }
-            let rotated_offset = rotated_offset_vec[0];
+            // Calculate target grid position, following the item's rotation and flip
+            let rotated_offset = orient_shape(&[synergy.offset], rot.0, flip.0)[0];
             let target_pos = IVec2::new(pos.0.x, pos.0.y) + rotated_offset;
 
             // Check if occupied
             // New logic: Check occupancy map directly
             if let Some(target_entity) = grid_state.occupancy.get(&target_pos) {
                 // Check tags
-                if let Ok(target_def) = q_tags.get(*target_entity) {
+                let target_def = q_targets.get(*target_entity).ok().and_then(|t| item_db.items.get(&t.item_id));
+                if let Some(target_def) = target_def {
                     if synergy.target_tags.iter().any(|req| target_def.tags.contains(req)) {
                             // Match found! Draw line.
                             if let Ok(target_transform) = q_transforms.get(*target_entity) {
@@ -65,19 +76,36 @@ fn draw_synergy_lines(
 
 /// Draws lines for potential recipes.
 /// Blue: Potential (neighboring ingredient).
-/// Gold: Ready (all ingredients present).
+/// Gold: Ready (all ingredients and catalysts connected, see `ReadyRecipes`).
 fn draw_recipe_lines(
     mut gizmos: Gizmos,
-    q_items: Query<(Entity, &GridPosition, &InventoryItem, &ItemRotation)>,
+    q_items: Query<(Entity, &GridPosition, &InventoryItem, &ItemRotation, &ItemFlip), Without<InStorage>>,
     item_db: Res<ItemDatabase>,
+    ready: Res<ReadyRecipes>,
     q_transforms: Query<&GlobalTransform>,
 ) {
     if item_db.recipes.is_empty() { return; }
 
+    // Gold: link every adjacent pair inside a ready group
+    for found in &ready.matches {
+        let group: Vec<Entity> = found.ingredients.iter().chain(found.catalysts.iter()).copied().collect();
+        for (i, entity_a) in group.iter().enumerate() {
+            for entity_b in &group[i + 1..] {
+                let (Ok((_, pos_a, item_a, rot_a, flip_a)), Ok((_, pos_b, item_b, rot_b, flip_b))) =
+                    (q_items.get(*entity_a), q_items.get(*entity_b)) else { continue; };
+                if !are_adjacent(pos_a, (rot_a.0, flip_a.0), item_a, pos_b, (rot_b.0, flip_b.0), item_b) { continue; }
+
+                if let (Ok(t_a), Ok(t_b)) = (q_transforms.get(*entity_a), q_transforms.get(*entity_b)) {
+                    gizmos.line_2d(t_a.translation().truncate(), t_b.translation().truncate(), Color::srgb(1.0, 0.84, 0.0));
+                }
+            }
+        }
+    }
+
     // Collect all items on grid
-    let mut items_on_grid: Vec<(Entity, &InventoryItem, &GridPosition, &ItemRotation)> = Vec::new();
-    for (e, pos, item, rot) in q_items.iter() {
-        items_on_grid.push((e, item, pos, rot));
+    let mut items_on_grid: Vec<(Entity, &InventoryItem, &GridPosition, (u8, bool))> = Vec::new();
+    for (e, pos, item, rot, flip) in q_items.iter() {
+        items_on_grid.push((e, item, pos, (rot.0, flip.0)));
     }
 
     // Naive O(R * N^2) check. R=recipes, N=items. N is small (~20).
@@ -97,9 +125,13 @@ fn draw_recipe_lines(
                 for (entity_a, item_a, pos_a, rot_a) in &items_a {
                     for (entity_b, item_b, pos_b, rot_b) in &items_b {
                         if entity_a == entity_b { continue; }
+                        // Already drawn in gold
+                        if let (Some(m_a), Some(m_b)) = (ready.match_for(*entity_a), ready.match_for(*entity_b)) {
+                            if m_a == m_b { continue; }
+                        }
 
                         // Check adjacency
-                        if are_adjacent(pos_a, rot_a, item_a, pos_b, rot_b, item_b) {
+                        if are_adjacent(pos_a, *rot_a, item_a, pos_b, *rot_b, item_b) {
                              // Draw line
                              if let (Ok(t_a), Ok(t_b)) = (q_transforms.get(*entity_a), q_transforms.get(*entity_b)) {
                                  let p1 = t_a.translation().truncate();
@@ -117,16 +149,20 @@ fn draw_recipe_lines(
     }
 }
 
+/// `orient_a`/`orient_b` are each item's (rotation, flipped).
 fn are_adjacent(
-    pos_a: &GridPosition, rot_a: &ItemRotation, item_a: &InventoryItem,
-    pos_b: &GridPosition, rot_b: &ItemRotation, item_b: &InventoryItem
+    pos_a: &GridPosition, orient_a: (u8, bool), item_a: &InventoryItem,
+    pos_b: &GridPosition, orient_b: (u8, bool), item_b: &InventoryItem
 ) -> bool {
+    let (rot_a, flip_a) = orient_a;
+    let (rot_b, flip_b) = orient_b;
+
     // Get all cells for A
-    let shape_a = rotate_shape(&item_a.base_shape, rot_a.0);
+    let shape_a = orient_shape(&item_a.base_shape, rot_a, flip_a);
     let cells_a: Vec<IVec2> = shape_a.iter().map(|offset| IVec2::new(pos_a.0.x, pos_a.0.y) + *offset).collect();
 
     // Get all cells for B
-    let shape_b = rotate_shape(&item_b.base_shape, rot_b.0);
+    let shape_b = orient_shape(&item_b.base_shape, rot_b, flip_b);
     let cells_b: Vec<IVec2> = shape_b.iter().map(|offset| IVec2::new(pos_b.0.x, pos_b.0.y) + *offset).collect();
 
     // Check if any cell in A is adjacent (dist 1) to any cell in B